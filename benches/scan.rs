@@ -0,0 +1,71 @@
+//! Scans a large synthetic program, checks the tokens match what the
+//! snippet scans to on its own, and reports time and allocations per token.
+//! Run with `cargo bench --bench scan`.
+
+use codecrafters_interpreter::Scanner;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+/// Counts every allocation so the scanner's allocations per token can be checked.
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+const SNIPPET: &str = "// running totals\n\
+var total = 0;\n\
+var name = \"lox scanner\";\n\
+for (var i = 0; i <= 100.5; i = i + 1) {\n\
+    if (i != 3 and !(total >= 42) or nil == false) total = total * 2 - i / 4;\n\
+}\n\
+print name; print total;\n";
+
+const REPETITIONS: usize = 20_000;
+
+fn main() {
+    let mut snippet = Scanner::new(SNIPPET.to_string());
+    snippet.scan_tokens();
+    let (snippet_eof, snippet_tokens) = snippet.tokens().split_last().unwrap();
+    let expected: Vec<String> = snippet_tokens.iter().map(ToString::to_string).collect();
+
+    let mut scanner = Scanner::new(SNIPPET.repeat(REPETITIONS));
+    let allocations_before = ALLOCATIONS.load(Ordering::Relaxed);
+    let start = Instant::now();
+    scanner.scan_tokens();
+    let elapsed = start.elapsed();
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - allocations_before;
+
+    let (eof, tokens) = scanner.tokens().split_last().unwrap();
+    assert!(!scanner.has_errors());
+    assert_eq!(tokens.len(), expected.len() * REPETITIONS);
+    for (index, token) in tokens.iter().enumerate() {
+        assert_eq!(token.to_string(), expected[index % expected.len()]);
+    }
+    assert_eq!(eof.to_string(), snippet_eof.to_string());
+
+    let per_token = allocations as f64 / scanner.tokens().len() as f64;
+    println!(
+        "scanned {} bytes into {} tokens in {:?} ({:.2} allocations per token)",
+        SNIPPET.len() * REPETITIONS,
+        scanner.tokens().len(),
+        elapsed,
+        per_token
+    );
+    // Operators and keywords borrow their lexeme, so only identifiers, numbers
+    // and strings allocate, and this snippet is mostly the former.
+    assert!(per_token < 1.0, "{:.2} allocations per token", per_token);
+}
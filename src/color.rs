@@ -0,0 +1,14 @@
+/// ANSI escape code for red foreground text.
+pub const RED: &str = "\x1b[31m";
+/// ANSI escape code for cyan foreground text.
+pub const CYAN: &str = "\x1b[36m";
+const RESET: &str = "\x1b[0m";
+
+/// Wraps `text` in the given ANSI color code when `enabled` is set.
+pub fn paint(text: &str, color: &str, enabled: bool) -> String {
+    if enabled {
+        format!("{}{}{}", color, text, RESET)
+    } else {
+        text.to_string()
+    }
+}
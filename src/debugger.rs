@@ -0,0 +1,109 @@
+use crate::environment::Environment;
+use crate::parser::Stmt;
+use std::io::{self, BufRead, Write};
+
+/// Interactive stepper for `run --step`. Before each statement it shows the
+/// statement and its line, then reads commands from `input`: an empty line
+/// steps to the next statement, `c` runs the rest of the program without
+/// pausing, and `p <name>` prints a variable. EOF on `input` acts like `c`.
+pub struct Debugger<R, W> {
+    input: R,
+    output: W,
+    stepping: bool,
+}
+
+impl<R: BufRead, W: Write> Debugger<R, W> {
+    pub fn new(input: R, output: W) -> Self {
+        Self {
+            input,
+            output,
+            stepping: true,
+        }
+    }
+
+    /// Pauses before `stmt` until told to go on; does nothing once stepping has stopped.
+    pub fn pause(&mut self, stmt: &Stmt, environment: &Environment) -> io::Result<()> {
+        if !self.stepping {
+            return Ok(());
+        }
+        writeln!(self.output, "[line {}] {}", stmt.line(), stmt)?;
+        loop {
+            write!(self.output, "(step) ")?;
+            self.output.flush()?;
+
+            let mut command = String::new();
+            if self.input.read_line(&mut command)? == 0 {
+                self.stepping = false;
+                return Ok(());
+            }
+            match command.trim() {
+                "" => return Ok(()),
+                "c" => {
+                    self.stepping = false;
+                    return Ok(());
+                }
+                command => match command.strip_prefix("p ").map(str::trim) {
+                    Some(name) => match environment.get(name, stmt.line()) {
+                        Ok(value) => writeln!(self.output, "{}", value)?,
+                        Err(error) => writeln!(self.output, "{}", error.message())?,
+                    },
+                    None => writeln!(self.output, "Unknown command: {}", command)?,
+                },
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interpreter::Interpreter;
+    use crate::parser::Parser;
+    use crate::scanner::Scanner;
+    use std::io::Cursor;
+
+    /// Runs `source` under the debugger with scripted `commands`, returning
+    /// the debugger transcript and the program's own output.
+    fn step(source: &str, commands: &str) -> (String, String) {
+        let mut scanner = Scanner::new(source.to_string());
+        scanner.scan_tokens();
+        let statements = Parser::new(scanner.tokens())
+            .parse()
+            .expect("source should parse");
+
+        let mut transcript = Vec::new();
+        let mut program_output = Vec::new();
+        let mut debugger = Debugger::new(Cursor::new(commands), &mut transcript);
+        Interpreter::with_output(&mut program_output)
+            .run_with_hook(&statements, |stmt, environment| {
+                debugger.pause(stmt, environment).unwrap()
+            })
+            .expect("program should run");
+        (
+            String::from_utf8(transcript).unwrap(),
+            String::from_utf8(program_output).unwrap(),
+        )
+    }
+
+    #[test]
+    fn print_command_shows_variables_as_they_change() {
+        let (transcript, output) =
+            step("var x = 1;\nx = x + 1;\nprint x;", "p x\n\np x\n\np x\nc\n");
+        assert_eq!(
+            transcript,
+            concat!(
+                "[line 1] (var x 1.0)\n(step) Undefined variable 'x'.\n(step) ",
+                "[line 2] (; (= x (+ x 1.0)))\n(step) 1\n(step) ",
+                "[line 3] (print x)\n(step) 2\n(step) ",
+            )
+        );
+        assert_eq!(output, "2\n");
+    }
+
+    #[test]
+    fn continue_stops_pausing() {
+        let (transcript, output) = step("print 1;\nprint 2;\nprint 3;", "c\n");
+        assert_eq!(transcript, "[line 1] (print 1.0)\n(step) ");
+        assert_eq!(output, "1\n2\n3\n");
+    }
+}
@@ -0,0 +1,81 @@
+use crate::interpreter::{RuntimeError, Value};
+use std::collections::HashMap;
+
+/// Storage for the variables declared by a program.
+#[derive(Default)]
+pub struct Environment {
+    values: HashMap<String, Value>,
+}
+
+impl Environment {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Binds `name` to `value`, replacing any previous binding; Lox allows redeclaring a variable.
+    pub fn define(&mut self, name: &str, value: Value) {
+        self.values.insert(name.to_string(), value);
+    }
+
+    pub fn get(&self, name: &str, line: usize) -> Result<Value, RuntimeError> {
+        self.values
+            .get(name)
+            .cloned()
+            .ok_or_else(|| undefined(name, line))
+    }
+
+    /// Rebinds an existing variable; unlike `define`, assigning to an undeclared name is an error.
+    pub fn assign(&mut self, name: &str, value: Value, line: usize) -> Result<(), RuntimeError> {
+        match self.values.get_mut(name) {
+            Some(slot) => {
+                *slot = value;
+                Ok(())
+            }
+            None => Err(undefined(name, line)),
+        }
+    }
+}
+
+fn undefined(name: &str, line: usize) -> RuntimeError {
+    RuntimeError::new(line, &format!("Undefined variable '{}'.", name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defined_variables_can_be_read_back() {
+        let mut environment = Environment::new();
+        environment.define("x", Value::Number(1.0));
+        assert_eq!(environment.get("x", 1).unwrap(), Value::Number(1.0));
+    }
+
+    #[test]
+    fn assignment_replaces_the_value() {
+        let mut environment = Environment::new();
+        environment.define("x", Value::Number(1.0));
+        environment
+            .assign("x", Value::Str("two".to_string()), 2)
+            .unwrap();
+        assert_eq!(
+            environment.get("x", 3).unwrap(),
+            Value::Str("two".to_string())
+        );
+    }
+
+    #[test]
+    fn undefined_variables_are_runtime_errors() {
+        let mut environment = Environment::new();
+        let error = environment
+            .get("x", 4)
+            .expect_err("reading an undefined variable should fail");
+        assert_eq!(error.message(), "Undefined variable 'x'.");
+        assert_eq!(error.line(), 4);
+
+        let error = environment
+            .assign("x", Value::Nil, 5)
+            .expect_err("assigning an undefined variable should fail");
+        assert_eq!(error.message(), "Undefined variable 'x'.");
+    }
+}
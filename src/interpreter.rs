@@ -0,0 +1,428 @@
+use crate::color::{paint, CYAN};
+use crate::environment::Environment;
+use crate::number::format_number;
+use crate::parser::{BinaryOperator, Expr, Literal, LogicalOperator, Stmt, UnaryOperator};
+use std::fmt::{Display, Formatter};
+use std::io::{self, Stdout, Write};
+
+/// Derived equality makes values of different types unequal, as Lox requires.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Number(f64),
+    Str(String),
+    Bool(bool),
+    Nil,
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("{message}\n[line {line}]")]
+pub struct RuntimeError {
+    line: usize,
+    message: String,
+}
+
+impl RuntimeError {
+    pub(crate) fn new(line: usize, message: &str) -> Self {
+        Self {
+            line,
+            message: message.to_string(),
+        }
+    }
+
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Prints the error to stderr as `<message>` followed by `[line N]`.
+    pub fn report(&self, color: bool) {
+        eprintln!(
+            "{}\n[line {}]",
+            self.message,
+            paint(&self.line.to_string(), CYAN, color)
+        );
+    }
+}
+
+/// Tree-walking interpreter; variables live as long as the interpreter does.
+/// `print` writes to `output`, standard output by default.
+pub struct Interpreter<W = Stdout> {
+    environment: Environment,
+    output: W,
+}
+
+impl Interpreter {
+    pub fn new() -> Self {
+        Self::with_output(io::stdout())
+    }
+}
+
+impl Default for Interpreter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<W: Write> Interpreter<W> {
+    pub fn with_output(output: W) -> Self {
+        Self {
+            environment: Environment::new(),
+            output,
+        }
+    }
+
+    /// Executes the statements of a program in order, stopping at the first runtime error.
+    pub fn run(&mut self, statements: &[Stmt]) -> Result<(), RuntimeError> {
+        self.run_with_hook(statements, |_, _| {})
+    }
+
+    /// Like `run`, but calls `before` with each statement and the current
+    /// variables just before executing it; this is how the debugger steps.
+    pub fn run_with_hook(
+        &mut self,
+        statements: &[Stmt],
+        mut before: impl FnMut(&Stmt, &Environment),
+    ) -> Result<(), RuntimeError> {
+        for statement in statements {
+            before(statement, &self.environment);
+            self.execute(statement)?;
+        }
+        Ok(())
+    }
+
+    fn execute(&mut self, statement: &Stmt) -> Result<(), RuntimeError> {
+        match statement {
+            Stmt::Print { values, .. } => {
+                let values = values
+                    .iter()
+                    .map(|value| self.evaluate(value).map(|value| value.to_string()))
+                    .collect::<Result<Vec<_>, _>>()?;
+                writeln!(self.output, "{}", values.join(" ")).expect("failed printing output");
+            }
+            Stmt::Expression { expr, .. } => {
+                self.evaluate(expr)?;
+            }
+            Stmt::Var {
+                name, initializer, ..
+            } => {
+                let value = match initializer {
+                    Some(expr) => self.evaluate(expr)?,
+                    None => Value::Nil,
+                };
+                self.environment.define(name, value);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn evaluate(&mut self, expr: &Expr) -> Result<Value, RuntimeError> {
+        match expr {
+            Expr::Literal(literal) => Ok(match literal {
+                Literal::Number(number) => Value::Number(*number),
+                Literal::String(string) => Value::Str(string.clone()),
+                Literal::Bool(boolean) => Value::Bool(*boolean),
+                Literal::Nil => Value::Nil,
+            }),
+            Expr::Grouping(expr) => self.evaluate(expr),
+            Expr::Unary {
+                operator,
+                right,
+                line,
+            } => {
+                let right = self.evaluate(right)?;
+                match operator {
+                    UnaryOperator::Minus => match right {
+                        Value::Number(number) => Ok(Value::Number(-number)),
+                        _ => Err(RuntimeError::new(*line, "Operand must be a number.")),
+                    },
+                    UnaryOperator::Bang => Ok(Value::Bool(!is_truthy(&right))),
+                }
+            }
+            Expr::Binary {
+                left,
+                operator,
+                right,
+                line,
+            } => {
+                let left = self.evaluate(left)?;
+                let right = self.evaluate(right)?;
+                binary(operator, left, right, *line)
+            }
+            Expr::Logical {
+                left,
+                operator,
+                right,
+            } => {
+                let left = self.evaluate(left)?;
+                let short_circuits = match operator {
+                    LogicalOperator::Or => is_truthy(&left),
+                    LogicalOperator::And => !is_truthy(&left),
+                };
+                if short_circuits {
+                    Ok(left)
+                } else {
+                    self.evaluate(right)
+                }
+            }
+            Expr::Variable { name, line } => self.environment.get(name, *line),
+            Expr::Assign { name, value, line } => {
+                let value = self.evaluate(value)?;
+                self.environment.assign(name, value.clone(), *line)?;
+                Ok(value)
+            }
+        }
+    }
+}
+
+/// `nil` and `false` are falsy; every other value, including `0` and `""`, is truthy.
+fn is_truthy(value: &Value) -> bool {
+    !matches!(value, Value::Nil | Value::Bool(false))
+}
+
+fn binary(
+    operator: &BinaryOperator,
+    left: Value,
+    right: Value,
+    line: usize,
+) -> Result<Value, RuntimeError> {
+    match operator {
+        BinaryOperator::EqualEqual => return Ok(Value::Bool(left == right)),
+        BinaryOperator::BangEqual => return Ok(Value::Bool(left != right)),
+        BinaryOperator::Plus => {
+            return match (left, right) {
+                (Value::Number(left), Value::Number(right)) => Ok(Value::Number(left + right)),
+                (Value::Str(left), Value::Str(right)) => Ok(Value::Str(left + &right)),
+                _ => Err(RuntimeError::new(
+                    line,
+                    "Operands must be two numbers or two strings.",
+                )),
+            }
+        }
+        _ => {}
+    }
+
+    let (Value::Number(left), Value::Number(right)) = (left, right) else {
+        return Err(RuntimeError::new(line, "Operands must be numbers."));
+    };
+    let value = match operator {
+        BinaryOperator::Minus => Value::Number(left - right),
+        BinaryOperator::Star => Value::Number(left * right),
+        BinaryOperator::Slash => Value::Number(left / right),
+        BinaryOperator::Less => Value::Bool(left < right),
+        BinaryOperator::LessEqual => Value::Bool(left <= right),
+        BinaryOperator::Greater => Value::Bool(left > right),
+        BinaryOperator::GreaterEqual => Value::Bool(left >= right),
+        BinaryOperator::Plus | BinaryOperator::EqualEqual | BinaryOperator::BangEqual => {
+            unreachable!("handled above")
+        }
+    };
+    Ok(value)
+}
+
+/// Prints the value the way Lox `print` does; integral numbers drop the `.0`.
+impl Display for Value {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Number(number) => write!(f, "{}", format_number(*number)),
+            Value::Str(string) => write!(f, "{}", string),
+            Value::Bool(boolean) => write!(f, "{}", boolean),
+            Value::Nil => write!(f, "nil"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+    use crate::scanner::Scanner;
+
+    /// Scans and parses `source` as a single expression, then evaluates it.
+    fn evaluate(source: &str) -> Result<Value, RuntimeError> {
+        let mut scanner = Scanner::new(source.to_string());
+        scanner.scan_tokens();
+        let expr = Parser::new(scanner.tokens())
+            .parse_expression()
+            .expect("source should parse");
+        Interpreter::new().evaluate(&expr)
+    }
+
+    /// Runs `source` as a program and returns the value of `name` afterwards.
+    fn variable_after(source: &str, name: &str) -> Value {
+        let mut scanner = Scanner::new(source.to_string());
+        scanner.scan_tokens();
+        let statements = Parser::new(scanner.tokens())
+            .parse()
+            .expect("source should parse");
+        let mut interpreter = Interpreter::new();
+        interpreter.run(&statements).expect("program should run");
+        interpreter
+            .environment
+            .get(name, 0)
+            .expect("variable should be defined")
+    }
+
+    fn error_message(source: &str) -> String {
+        evaluate(source)
+            .expect_err("expected a runtime error")
+            .message()
+            .to_string()
+    }
+
+    #[test]
+    fn operand_type_errors_use_the_lox_messages() {
+        assert_eq!(error_message("-\"foo\""), "Operand must be a number.");
+        assert_eq!(error_message("\"a\" * 2"), "Operands must be numbers.");
+        assert_eq!(
+            error_message("\"a\" + 1"),
+            "Operands must be two numbers or two strings."
+        );
+    }
+
+    #[test]
+    fn runtime_errors_carry_the_operator_line() {
+        let error = evaluate("1 +\n\n(\"a\" <\n 2)").expect_err("expected a runtime error");
+        assert_eq!(error.line(), 3);
+    }
+
+    #[test]
+    fn declared_variables_can_be_reassigned() {
+        assert_eq!(
+            variable_after("var x = 1; x = x + 2;", "x"),
+            Value::Number(3.0)
+        );
+        assert_eq!(
+            variable_after("var x = 1; var x = \"again\";", "x"),
+            Value::Str("again".to_string())
+        );
+        assert_eq!(
+            variable_after("var a; var b; a = b = 2;", "a"),
+            Value::Number(2.0)
+        );
+    }
+
+    #[test]
+    fn only_nil_and_false_are_falsy() {
+        assert_eq!(evaluate("!0").unwrap(), Value::Bool(false));
+        assert_eq!(evaluate("!nil").unwrap(), Value::Bool(true));
+        assert_eq!(evaluate("!\"\"").unwrap(), Value::Bool(false));
+    }
+
+    #[test]
+    fn equality_never_fails_across_types() {
+        assert_eq!(evaluate("\"a\" == \"a\"").unwrap(), Value::Bool(true));
+        assert_eq!(evaluate("nil == false").unwrap(), Value::Bool(false));
+        assert_eq!(evaluate("1 != \"1\"").unwrap(), Value::Bool(true));
+    }
+
+    #[test]
+    fn arithmetic_respects_grouping() {
+        assert_eq!(evaluate("(2 + 3) * 4").unwrap(), Value::Number(20.0));
+        assert_eq!(evaluate("2 + 3 * 4").unwrap(), Value::Number(14.0));
+    }
+
+    #[test]
+    fn declaration_without_initializer_binds_nil() {
+        assert_eq!(variable_after("var x;", "x"), Value::Nil);
+        assert_eq!(variable_after("var x; var y = x;", "y"), Value::Nil);
+        assert_eq!(variable_after("var x; x = 1;", "x"), Value::Number(1.0));
+    }
+
+    /// `(expression, expected print output)` pairs pinning number formatting.
+    const NUMBER_FIXTURES: &[(&str, &str)] = &[
+        ("123", "123"),
+        ("123.456", "123.456"),
+        ("0", "0"),
+        ("-0", "-0"),
+        ("0 * -1", "-0"),
+        ("007", "7"),
+        ("200.00", "200"),
+        ("1000000", "1000000"),
+        ("10000000000", "10000000000"),
+        ("10000000000 * 10000000000", "100000000000000000000"),
+        ("0.1 + 0.2", "0.30000000000000004"),
+        ("123456789012345680", "123456789012345680"),
+        ("1 / 3", "0.3333333333333333"),
+        ("1 / 1000000", "0.000001"),
+        ("-2.5 * 2", "-5"),
+        ("1 / 0", "Infinity"),
+        ("-1 / 0", "-Infinity"),
+        ("0 / 0", "NaN"),
+        ("1.5", "1.5"),
+        ("-1.5", "-1.5"),
+        ("-123", "-123"),
+        ("1.0", "1"),
+        ("-0.0", "-0"),
+        ("3.14159", "3.14159"),
+        ("2 * 0.5", "1"),
+        ("100 / 3", "33.333333333333336"),
+        ("0.1 * 3", "0.30000000000000004"),
+        ("1 - 0.9", "0.09999999999999998"),
+        ("12345.678 * 1000", "12345678"),
+        ("9007199254740993", "9007199254740992"),
+        ("99999999999999999", "100000000000000000"),
+        (
+            "100000000000000000000 * 100000000000000000000",
+            "10000000000000000000000000000000000000000",
+        ),
+        ("1 / 100000000000000000000", "0.00000000000000000001"),
+        (
+            "1 / 100000000000000000000 / 100000000000000000000",
+            "0.0000000000000000000000000000000000000001",
+        ),
+    ];
+
+    #[test]
+    fn numbers_print_like_reference_lox() {
+        for (source, expected) in NUMBER_FIXTURES {
+            let printed = evaluate(source).unwrap().to_string();
+            assert_eq!(printed, *expected, "evaluating {}", source);
+        }
+    }
+
+    #[test]
+    fn googol_prints_without_exponent() {
+        // Lox has no exponent syntax, so 1e100 is written out in full.
+        let googol = format!("1{}", "0".repeat(100));
+        assert_eq!(evaluate(&googol).unwrap().to_string(), googol);
+    }
+
+    #[test]
+    fn exponent_notation_is_not_a_number_literal() {
+        let mut scanner = Scanner::new("1e100".to_string());
+        scanner.scan_tokens();
+        Parser::new(scanner.tokens())
+            .parse_expression()
+            .expect_err("1e100 should not parse");
+    }
+
+    #[test]
+    fn logical_operators_return_the_deciding_operand() {
+        assert_eq!(
+            evaluate("nil or \"x\"").unwrap(),
+            Value::Str("x".to_string())
+        );
+        assert_eq!(evaluate("1 and 2").unwrap(), Value::Number(2.0));
+        assert_eq!(evaluate("0 or 2").unwrap(), Value::Number(0.0));
+        assert_eq!(evaluate("nil and 2").unwrap(), Value::Nil);
+    }
+
+    #[test]
+    fn logical_operators_short_circuit() {
+        assert_eq!(
+            evaluate("false and undefined_var").unwrap(),
+            Value::Bool(false)
+        );
+        assert_eq!(
+            evaluate("true or undefined_var").unwrap(),
+            Value::Bool(true)
+        );
+        assert_eq!(
+            evaluate("true and undefined_var").unwrap_err().message(),
+            "Undefined variable 'undefined_var'."
+        );
+    }
+}
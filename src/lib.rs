@@ -0,0 +1,11 @@
+mod color;
+pub mod debugger;
+pub mod environment;
+pub mod interpreter;
+mod number;
+pub mod parser;
+pub mod repl;
+pub mod scanner;
+pub mod visitor;
+
+pub use scanner::{LoxError, Scanner, ScannerConfig, Token, TokenType};
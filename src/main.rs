@@ -1,44 +1,319 @@
-mod scanner;
-
-use crate::scanner::Scanner;
+use codecrafters_interpreter::debugger::Debugger;
+use codecrafters_interpreter::interpreter::Interpreter;
+use codecrafters_interpreter::parser::{Parser, Stmt};
+use codecrafters_interpreter::{repl, Scanner};
 use std::env;
-use std::fs;
-use std::io::{self, Write};
+use std::io::{self, IsTerminal};
+use std::path::Path;
+use std::time::Instant;
 
 fn main() {
     let args: Vec<String> = env::args().collect();
-    if args.len() < 3 {
-        writeln!(io::stderr(), "Usage: {} tokenize <filename>", args[0]).unwrap();
-        return;
-    }
-
-    let command = &args[1];
-    let filename = &args[2];
+    let Some(command) = args.get(1) else {
+        eprintln!("No command given.");
+        exit_with_usage(&args[0]);
+    };
+    let Some(supported) = supported_flags(command) else {
+        eprintln!("Unknown command: {}", command);
+        exit_with_usage(&args[0]);
+    };
+    let (flags, filename) = match command.as_str() {
+        // The repl reads standard input, so every argument is a flag.
+        "repl" => (&args[2..], "-"),
+        _ if args.len() < 3 => exit_with_usage(&args[0]),
+        _ => (&args[2..args.len() - 1], args[args.len() - 1].as_str()),
+    };
+    check_flags(&args[0], command, supported, flags);
+    let has_flag = |name: &str| flags.iter().any(|flag| flag == name);
+    let flag_value = |name: &str| {
+        flags
+            .iter()
+            .position(|flag| flag == name)
+            .and_then(|index| flags.get(index + 1))
+            .map(String::as_str)
+    };
+    let color = use_color(flag_value("--color"));
+    let max_token_length = match flag_value("--max-token-length").map(str::parse::<usize>) {
+        Some(Ok(limit)) => Some(limit),
+        Some(Err(_)) => {
+            eprintln!("--max-token-length expects a non-negative integer");
+            std::process::exit(64);
+        }
+        None => None,
+    };
 
     match command.as_str() {
+        "repl" => {
+            if let Err(error) = repl::run(io::stdin().lock(), io::stdout(), io::stderr(), color) {
+                eprintln!("{}", error);
+            }
+        }
         "tokenize" => {
-            let file_contents = fs::read_to_string(filename).unwrap_or_else(|_| {
-                writeln!(io::stderr(), "Failed to read file {}", filename).unwrap();
-                String::new()
-            });
-
-            if !file_contents.is_empty() {
-                let mut scanner = Scanner::new(file_contents);
-                scanner.scan_tokens();
+            let mut scanner = open_scanner(filename, max_token_length);
+            scanner.scan_tokens();
+            if has_flag("--check") {
+                scanner.report_errors(color);
+                std::process::exit(if scanner.has_errors() { 65 } else { 0 });
+            }
+
+            if has_flag("--skip-errors") {
+                scanner.discard_unexpected_characters();
+            }
+            let json = has_flag("--json");
+            if !json {
+                scanner.report_errors(color);
+            }
+            if json {
+                println!("{}", scanner.to_json());
+            } else if has_flag("--dump-constants") {
+                for literal in scanner.tokens().iter().filter_map(|token| token.literal()) {
+                    println!("{}", literal);
+                }
+            } else {
                 println!("{}", scanner);
+            }
+
+            if scanner.has_errors() && !has_flag("--exit-zero-on-lex-error") {
+                std::process::exit(65);
+            } else {
+                std::process::exit(0);
+            }
+        }
+        "classify" => {
+            let mut scanner = open_scanner(filename, max_token_length);
+            scanner.scan_tokens();
+            scanner.report_errors(color);
+            for token in scanner.tokens() {
+                if let Some(category) = token.token_type().category() {
+                    println!("{} {}", category, token.lexeme());
+                }
+            }
+
+            if scanner.has_errors() {
+                std::process::exit(65);
+            }
+        }
+        "parse" => {
+            let mut scanner = open_scanner(filename, max_token_length);
+            scanner.scan_tokens();
+            if scanner.has_errors() {
+                scanner.report_errors(color);
+                std::process::exit(65);
+            }
 
-                if scanner.has_errors() {
+            match Parser::new(scanner.tokens()).parse_expression() {
+                Ok(expr) => println!("{}", expr),
+                Err(error) => {
+                    error.report(color);
                     std::process::exit(65);
-                } else {
-                    std::process::exit(0);
                 }
+            }
+        }
+        "evaluate" => {
+            let mut scanner = open_scanner(filename, max_token_length);
+            scanner.scan_tokens();
+            if scanner.has_errors() {
+                scanner.report_errors(color);
+                std::process::exit(65);
+            }
+
+            let expr = match Parser::new(scanner.tokens()).parse_expression() {
+                Ok(expr) => expr,
+                Err(error) => {
+                    error.report(color);
+                    std::process::exit(65);
+                }
+            };
+            match Interpreter::new().evaluate(&expr) {
+                Ok(value) => println!("{}", value),
+                Err(error) => {
+                    error.report(color);
+                    std::process::exit(70);
+                }
+            }
+        }
+        "run" => {
+            let scanner = open_scanner(filename, max_token_length);
+            let statements = parse_program(scanner, has_flag("--multi-print"), color);
+            let mut interpreter = Interpreter::new();
+            let result = if has_flag("--step") {
+                // The debugger talks over stdin and stderr so the program's own output stays on stdout.
+                let mut debugger = Debugger::new(io::stdin().lock(), io::stderr());
+                interpreter.run_with_hook(&statements, |stmt, environment| {
+                    debugger
+                        .pause(stmt, environment)
+                        .expect("failed talking to the debugger")
+                })
             } else {
-                println!("EOF  null"); // Placeholder, replace this line when implementing the scanner
+                interpreter.run(&statements)
+            };
+            if let Err(error) = result {
+                error.report(color);
+                std::process::exit(70);
+            }
+        }
+        "bench" => {
+            let iterations = match flag_value("--iterations").map(str::parse::<usize>) {
+                None => 10,
+                Some(Ok(iterations)) if iterations > 0 => iterations,
+                Some(_) => {
+                    eprintln!("--iterations expects a positive integer");
+                    std::process::exit(64);
+                }
+            };
+            let scanner = open_scanner(filename, max_token_length);
+            let statements = parse_program(scanner, has_flag("--multi-print"), color);
+
+            // Each run gets a fresh environment; only the first one's output is shown.
+            let mut timings = Vec::with_capacity(iterations);
+            for iteration in 0..iterations {
+                let start = Instant::now();
+                let result = if iteration == 0 {
+                    Interpreter::new().run(&statements)
+                } else {
+                    Interpreter::with_output(io::sink()).run(&statements)
+                };
+                timings.push(start.elapsed());
+                if let Err(error) = result {
+                    error.report(color);
+                    std::process::exit(70);
+                }
             }
+            timings.sort();
+            eprintln!(
+                "{} iterations: min {:?}, median {:?}, max {:?}",
+                iterations,
+                timings[0],
+                timings[iterations / 2],
+                timings[iterations - 1]
+            );
+        }
+        _ => unreachable!("supported_flags only knows the commands above"),
+    }
+}
+
+/// Flags that stand alone.
+const SWITCHES: &[&str] = &[
+    "--check",
+    "--skip-errors",
+    "--json",
+    "--dump-constants",
+    "--exit-zero-on-lex-error",
+    "--multi-print",
+    "--step",
+];
+/// Flags that take the following argument as their value.
+const OPTIONS: &[&str] = &["--color", "--max-token-length", "--iterations"];
+
+/// The flags each command accepts, or `None` for an unknown command.
+fn supported_flags(command: &str) -> Option<&'static [&'static str]> {
+    let flags: &[&str] = match command {
+        "tokenize" => &[
+            "--check",
+            "--skip-errors",
+            "--json",
+            "--dump-constants",
+            "--exit-zero-on-lex-error",
+            "--color",
+            "--max-token-length",
+        ],
+        "classify" | "parse" | "evaluate" => &["--color", "--max-token-length"],
+        "repl" => &["--color"],
+        "run" => &["--multi-print", "--step", "--color", "--max-token-length"],
+        "bench" => &[
+            "--iterations",
+            "--multi-print",
+            "--color",
+            "--max-token-length",
+        ],
+        _ => return None,
+    };
+    Some(flags)
+}
+
+/// Exits with 64 on an unknown flag, a flag `command` doesn't support, or an
+/// option missing its value, so typos don't silently fall back to the default behavior.
+fn check_flags(program: &str, command: &str, supported: &[&str], flags: &[String]) {
+    let mut flags = flags.iter().map(String::as_str);
+    while let Some(flag) = flags.next() {
+        if !OPTIONS.contains(&flag) && !SWITCHES.contains(&flag) {
+            eprintln!("Unknown flag: {}", flag);
+            exit_with_usage(program);
+        }
+        if !supported.contains(&flag) {
+            eprintln!("{} does not support {}", command, flag);
+            exit_with_usage(program);
+        }
+        if OPTIONS.contains(&flag) && flags.next().is_none() {
+            eprintln!("{} expects a value", flag);
+            exit_with_usage(program);
+        }
+    }
+}
+
+/// Prints usage to stderr and exits with 64, the conventional usage-error code.
+fn exit_with_usage(program: &str) -> ! {
+    eprintln!(
+        "Usage: {} <tokenize | classify | parse | evaluate | run | bench> [flags] <filename | ->",
+        program
+    );
+    eprintln!("       {} repl [--color <always | never | auto>]", program);
+    std::process::exit(64);
+}
+
+/// Scans and parses a whole program, exiting with 65 on lexical or parse errors.
+fn parse_program(mut scanner: Scanner, multi_print: bool, color: bool) -> Vec<Stmt> {
+    scanner.scan_tokens();
+    if scanner.has_errors() {
+        scanner.report_errors(color);
+        std::process::exit(65);
+    }
+
+    match Parser::new(scanner.tokens())
+        .with_multi_print(multi_print)
+        .parse()
+    {
+        Ok(statements) => statements,
+        Err(error) => {
+            error.report(color);
+            std::process::exit(65);
+        }
+    }
+}
+
+/// Opens a scanner over `filename`, or over standard input when it is `-`.
+/// Exits with 66, the conventional "cannot open input" code, if it can't be read.
+fn open_scanner(filename: &str, max_token_length: Option<usize>) -> Scanner {
+    let source = if filename == "-" {
+        io::read_to_string(io::stdin()).map(Scanner::new)
+    } else {
+        Scanner::from_file(Path::new(filename))
+    };
+    let scanner = source.unwrap_or_else(|error| {
+        eprintln!("Failed to read file {}: {}", filename, error);
+        std::process::exit(66);
+    });
+    match max_token_length {
+        Some(limit) => scanner.with_max_token_length(limit),
+        None => scanner,
+    }
+}
+
+/// Decides whether diagnostics should be colored from the `--color` value
+/// (`always`, `never`, or `auto` by default), exiting with 64 on any other
+/// value. In auto mode a non-empty `NO_COLOR` disables color; an empty one
+/// is ignored, per the convention.
+fn use_color(choice: Option<&str>) -> bool {
+    match choice {
+        Some("always") => true,
+        Some("never") => false,
+        None | Some("auto") => {
+            let no_color = env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+            !no_color && io::stderr().is_terminal()
         }
-        _ => {
-            writeln!(io::stderr(), "Unknown command: {}", command).unwrap();
-            return;
+        Some(other) => {
+            eprintln!("--color expects always, never or auto, got {}", other);
+            std::process::exit(64);
         }
     }
 }
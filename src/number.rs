@@ -0,0 +1,52 @@
+/// Formats a number in its shortest decimal form, never in exponent notation:
+/// `42`, `42.5`, `-0`, `10000000000`. This is how Lox prints runtime values;
+/// non-finite values follow the reference spelling `Infinity`, `-Infinity` and `NaN`.
+pub fn format_number(n: f64) -> String {
+    if n.is_nan() {
+        "NaN".to_string()
+    } else if n.is_infinite() {
+        if n > 0.0 { "Infinity" } else { "-Infinity" }.to_string()
+    } else {
+        n.to_string()
+    }
+}
+
+/// Formats a number the way literals appear in token and AST output, which
+/// always carry a fractional part: `42.0`, `42.5`, `-0.0`, `10000000000.0`.
+pub fn format_number_literal(n: f64) -> String {
+    let formatted = format_number(n);
+    if n.is_finite() && !formatted.contains('.') {
+        formatted + ".0"
+    } else {
+        formatted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn values_drop_the_fraction_when_integral() {
+        assert_eq!(format_number(42.0), "42");
+        assert_eq!(format_number(42.5), "42.5");
+        assert_eq!(format_number(-0.0), "-0");
+        assert_eq!(format_number(1e10), "10000000000");
+    }
+
+    #[test]
+    fn literals_always_carry_a_fraction() {
+        assert_eq!(format_number_literal(42.0), "42.0");
+        assert_eq!(format_number_literal(42.5), "42.5");
+        assert_eq!(format_number_literal(-0.0), "-0.0");
+        assert_eq!(format_number_literal(1e10), "10000000000.0");
+    }
+
+    #[test]
+    fn non_finite_values_use_the_reference_spelling() {
+        assert_eq!(format_number(f64::INFINITY), "Infinity");
+        assert_eq!(format_number(f64::NEG_INFINITY), "-Infinity");
+        assert_eq!(format_number(f64::NAN), "NaN");
+        assert_eq!(format_number_literal(f64::INFINITY), "Infinity");
+    }
+}
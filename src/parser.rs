@@ -0,0 +1,582 @@
+use crate::color::{paint, CYAN, RED};
+use crate::number::format_number_literal;
+use crate::scanner::{Token, TokenType};
+use crate::visitor::Visitor;
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug)]
+pub enum Literal {
+    Number(f64),
+    String(String),
+    Bool(bool),
+    Nil,
+}
+
+#[derive(Debug)]
+pub enum UnaryOperator {
+    /// `-` Arithmetic negation
+    Minus,
+    /// `!` Logical negation
+    Bang,
+}
+
+#[derive(Debug)]
+pub enum BinaryOperator {
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    EqualEqual,
+    BangEqual,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+}
+
+#[derive(Debug)]
+pub enum LogicalOperator {
+    And,
+    Or,
+}
+
+#[derive(Debug)]
+pub enum Expr {
+    Literal(Literal),
+    Grouping(Box<Expr>),
+    Unary {
+        operator: UnaryOperator,
+        right: Box<Expr>,
+        /// Line of the operator token, for runtime error reporting
+        line: usize,
+    },
+    Binary {
+        left: Box<Expr>,
+        operator: BinaryOperator,
+        right: Box<Expr>,
+        /// Line of the operator token, for runtime error reporting
+        line: usize,
+    },
+    /// `and`/`or`, kept apart from `Binary` because the right operand may not be evaluated
+    Logical {
+        left: Box<Expr>,
+        operator: LogicalOperator,
+        right: Box<Expr>,
+    },
+    Variable {
+        name: String,
+        /// Line of the identifier, for runtime error reporting
+        line: usize,
+    },
+    /// `<name> = <value>`
+    Assign {
+        name: String,
+        value: Box<Expr>,
+        /// Line of the identifier, for runtime error reporting
+        line: usize,
+    },
+}
+
+/// Each statement records the line it starts on, for the `--step` debugger.
+#[derive(Debug)]
+pub enum Stmt {
+    /// `print <expr>;`, or `print <expr>, <expr>, ...;` when multi-print is enabled
+    Print { values: Vec<Expr>, line: usize },
+    /// An expression evaluated for its side effects, `<expr>;`
+    Expression { expr: Expr, line: usize },
+    /// `var <name>;` or `var <name> = <initializer>;`
+    Var {
+        name: String,
+        initializer: Option<Expr>,
+        line: usize,
+    },
+}
+
+impl Stmt {
+    pub fn line(&self) -> usize {
+        match self {
+            Stmt::Print { line, .. } | Stmt::Expression { line, .. } | Stmt::Var { line, .. } => {
+                *line
+            }
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("[line {line}] Error{location}: {message}")]
+pub struct ParseError {
+    line: usize,
+    /// Where the error occurred, e.g. ` at 'foo'` or ` at end`
+    location: String,
+    message: String,
+}
+
+/// Recursive-descent parser over the tokens produced by the `Scanner`.
+pub struct Parser<'a> {
+    tokens: &'a [Token],
+    current: usize,
+    multi_print: bool,
+}
+
+impl ParseError {
+    fn new(token: &Token, message: &str) -> Self {
+        let location = match token.token_type() {
+            TokenType::Eof => " at end".to_string(),
+            _ => format!(" at '{}'", token.lexeme()),
+        };
+        Self {
+            line: token.line(),
+            location,
+            message: message.to_string(),
+        }
+    }
+
+    /// Prints the error to stderr, ANSI-colored when `color` is set.
+    pub fn report(&self, color: bool) {
+        eprintln!(
+            "[line {}] {}{}: {}",
+            paint(&self.line.to_string(), CYAN, color),
+            paint("Error", RED, color),
+            self.location,
+            self.message
+        );
+    }
+}
+
+impl<'a> Parser<'a> {
+    /// Creates a parser over `tokens`, which must end with an `Eof` token.
+    pub fn new(tokens: &'a [Token]) -> Self {
+        Self {
+            tokens,
+            current: 0,
+            multi_print: false,
+        }
+    }
+
+    /// Lets `print` take a comma-separated list of expressions when `enabled` is set.
+    pub fn with_multi_print(mut self, enabled: bool) -> Self {
+        self.multi_print = enabled;
+        self
+    }
+
+    /// Parses the whole token stream as a single expression; leftover tokens are an error.
+    pub fn parse_expression(&mut self) -> Result<Expr, ParseError> {
+        let expr = self.expression()?;
+        self.consume(TokenType::Eof, "Expect end of expression.")?;
+        Ok(expr)
+    }
+
+    /// Parses the whole token stream as a program, i.e. a sequence of statements.
+    pub fn parse(&mut self) -> Result<Vec<Stmt>, ParseError> {
+        let mut statements = Vec::new();
+        while *self.peek().token_type() != TokenType::Eof {
+            statements.push(self.declaration()?);
+        }
+        Ok(statements)
+    }
+
+    fn declaration(&mut self) -> Result<Stmt, ParseError> {
+        if *self.peek().token_type() != TokenType::Var {
+            return self.statement();
+        }
+        let line = self.peek().start_line();
+        self.advance();
+        let name = self.consume(TokenType::Identifier, "Expect variable name.")?;
+        let initializer = if *self.peek().token_type() == TokenType::Equal {
+            self.advance();
+            Some(self.expression()?)
+        } else {
+            None
+        };
+        self.consume(
+            TokenType::Semicolon,
+            "Expect ';' after variable declaration.",
+        )?;
+        Ok(Stmt::Var {
+            name: name.lexeme().to_string(),
+            initializer,
+            line,
+        })
+    }
+
+    fn statement(&mut self) -> Result<Stmt, ParseError> {
+        let line = self.peek().start_line();
+        if *self.peek().token_type() == TokenType::Print {
+            self.advance();
+            let mut values = vec![self.expression()?];
+            while self.multi_print && *self.peek().token_type() == TokenType::Comma {
+                self.advance();
+                values.push(self.expression()?);
+            }
+            self.consume(TokenType::Semicolon, "Expect ';' after value.")?;
+            return Ok(Stmt::Print { values, line });
+        }
+
+        let expr = self.expression()?;
+        self.consume(TokenType::Semicolon, "Expect ';' after expression.")?;
+        Ok(Stmt::Expression { expr, line })
+    }
+
+    fn expression(&mut self) -> Result<Expr, ParseError> {
+        self.assignment()
+    }
+
+    /// Assignment is right-associative, and its target is only known to be a
+    /// variable once the left-hand side has been parsed as an expression.
+    fn assignment(&mut self) -> Result<Expr, ParseError> {
+        let expr = self.or()?;
+        if *self.peek().token_type() != TokenType::Equal {
+            return Ok(expr);
+        }
+        let equals = self.peek();
+        self.advance();
+        let value = self.assignment()?;
+        match expr {
+            Expr::Variable { name, line } => Ok(Expr::Assign {
+                name,
+                value: Box::new(value),
+                line,
+            }),
+            _ => Err(ParseError::new(equals, "Invalid assignment target.")),
+        }
+    }
+
+    fn or(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.and()?;
+        while *self.peek().token_type() == TokenType::Or {
+            self.advance();
+            let right = self.and()?;
+            expr = Expr::logical(expr, LogicalOperator::Or, right);
+        }
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.equality()?;
+        while *self.peek().token_type() == TokenType::And {
+            self.advance();
+            let right = self.equality()?;
+            expr = Expr::logical(expr, LogicalOperator::And, right);
+        }
+        Ok(expr)
+    }
+
+    fn equality(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.comparison()?;
+        while let Some((operator, line)) = self.binary_operator(|token_type| match token_type {
+            TokenType::EqualEqual => Some(BinaryOperator::EqualEqual),
+            TokenType::BangEqual => Some(BinaryOperator::BangEqual),
+            _ => None,
+        }) {
+            let right = self.comparison()?;
+            expr = Expr::binary(expr, operator, right, line);
+        }
+        Ok(expr)
+    }
+
+    fn comparison(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.term()?;
+        while let Some((operator, line)) = self.binary_operator(|token_type| match token_type {
+            TokenType::Less => Some(BinaryOperator::Less),
+            TokenType::LessEqual => Some(BinaryOperator::LessEqual),
+            TokenType::Greater => Some(BinaryOperator::Greater),
+            TokenType::GreaterEqual => Some(BinaryOperator::GreaterEqual),
+            _ => None,
+        }) {
+            let right = self.term()?;
+            expr = Expr::binary(expr, operator, right, line);
+        }
+        Ok(expr)
+    }
+
+    fn term(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.factor()?;
+        while let Some((operator, line)) = self.binary_operator(|token_type| match token_type {
+            TokenType::Plus => Some(BinaryOperator::Plus),
+            TokenType::Minus => Some(BinaryOperator::Minus),
+            _ => None,
+        }) {
+            let right = self.factor()?;
+            expr = Expr::binary(expr, operator, right, line);
+        }
+        Ok(expr)
+    }
+
+    fn factor(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.unary()?;
+        while let Some((operator, line)) = self.binary_operator(|token_type| match token_type {
+            TokenType::Star => Some(BinaryOperator::Star),
+            TokenType::Slash => Some(BinaryOperator::Slash),
+            _ => None,
+        }) {
+            let right = self.unary()?;
+            expr = Expr::binary(expr, operator, right, line);
+        }
+        Ok(expr)
+    }
+
+    fn unary(&mut self) -> Result<Expr, ParseError> {
+        let operator = match self.peek().token_type() {
+            TokenType::Minus => UnaryOperator::Minus,
+            TokenType::Bang => UnaryOperator::Bang,
+            _ => return self.primary(),
+        };
+        let line = self.peek().line();
+        self.advance();
+        let right = self.unary()?;
+        Ok(Expr::Unary {
+            operator,
+            right: Box::new(right),
+            line,
+        })
+    }
+
+    fn primary(&mut self) -> Result<Expr, ParseError> {
+        let token = self.peek();
+        let literal = match token.token_type() {
+            TokenType::False => Literal::Bool(false),
+            TokenType::True => Literal::Bool(true),
+            TokenType::Nil => Literal::Nil,
+            TokenType::Number => Literal::Number(
+                token
+                    .literal()
+                    .and_then(|literal| literal.parse().ok())
+                    .expect("Number token should carry a numeric literal"),
+            ),
+            TokenType::String => Literal::String(token.literal().unwrap_or("").to_string()),
+            TokenType::Identifier => {
+                self.advance();
+                return Ok(Expr::Variable {
+                    name: token.lexeme().to_string(),
+                    line: token.line(),
+                });
+            }
+            TokenType::LeftParen => {
+                self.advance();
+                let expr = self.expression()?;
+                self.consume(TokenType::RightParen, "Expect ')' after expression.")?;
+                return Ok(Expr::Grouping(Box::new(expr)));
+            }
+            _ => return Err(ParseError::new(token, "Expect expression.")),
+        };
+        self.advance();
+        Ok(Expr::Literal(literal))
+    }
+
+    /// Consumes and returns the next token if it has the `expected` type, failing with `message` otherwise.
+    fn consume(&mut self, expected: TokenType, message: &str) -> Result<&'a Token, ParseError> {
+        let token = self.peek();
+        if *token.token_type() == expected {
+            self.advance();
+            Ok(token)
+        } else {
+            Err(ParseError::new(self.peek(), message))
+        }
+    }
+
+    /// Consumes the next token if `select` maps it to a binary operator,
+    /// returning the operator together with its line.
+    fn binary_operator(
+        &mut self,
+        select: impl Fn(&TokenType) -> Option<BinaryOperator>,
+    ) -> Option<(BinaryOperator, usize)> {
+        let token = self.peek();
+        let operator = select(token.token_type())?;
+        self.advance();
+        Some((operator, token.line()))
+    }
+
+    fn peek(&self) -> &'a Token {
+        &self.tokens[self.current]
+    }
+
+    fn advance(&mut self) {
+        if !matches!(self.peek().token_type(), TokenType::Eof) {
+            self.current += 1;
+        }
+    }
+}
+
+impl Expr {
+    fn binary(left: Expr, operator: BinaryOperator, right: Expr, line: usize) -> Self {
+        Expr::Binary {
+            left: Box::new(left),
+            operator,
+            right: Box::new(right),
+            line,
+        }
+    }
+
+    fn logical(left: Expr, operator: LogicalOperator, right: Expr) -> Self {
+        Expr::Logical {
+            left: Box::new(left),
+            operator,
+            right: Box::new(right),
+        }
+    }
+}
+
+impl Display for LogicalOperator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LogicalOperator::And => write!(f, "and"),
+            LogicalOperator::Or => write!(f, "or"),
+        }
+    }
+}
+
+impl Display for Literal {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Literal::Number(number) => write!(f, "{}", format_number_literal(*number)),
+            Literal::String(string) => write!(f, "{}", string),
+            Literal::Bool(boolean) => write!(f, "{}", boolean),
+            Literal::Nil => write!(f, "nil"),
+        }
+    }
+}
+
+impl Display for UnaryOperator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UnaryOperator::Minus => write!(f, "-"),
+            UnaryOperator::Bang => write!(f, "!"),
+        }
+    }
+}
+
+impl Display for BinaryOperator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BinaryOperator::Plus => write!(f, "+"),
+            BinaryOperator::Minus => write!(f, "-"),
+            BinaryOperator::Star => write!(f, "*"),
+            BinaryOperator::Slash => write!(f, "/"),
+            BinaryOperator::EqualEqual => write!(f, "=="),
+            BinaryOperator::BangEqual => write!(f, "!="),
+            BinaryOperator::Less => write!(f, "<"),
+            BinaryOperator::LessEqual => write!(f, "<="),
+            BinaryOperator::Greater => write!(f, ">"),
+            BinaryOperator::GreaterEqual => write!(f, ">="),
+        }
+    }
+}
+
+/// Renders expressions in the canonical parenthesized form, e.g. `(* (- 3) (group 4.5))`.
+#[derive(Default)]
+struct AstPrinter {
+    output: String,
+}
+
+impl AstPrinter {
+    fn parenthesize(&mut self, name: &str, operands: &[&Expr]) {
+        self.output.push('(');
+        self.output.push_str(name);
+        for operand in operands {
+            self.output.push(' ');
+            self.visit_expr(operand);
+        }
+        self.output.push(')');
+    }
+}
+
+impl Visitor for AstPrinter {
+    fn visit_stmt(&mut self, stmt: &Stmt) {
+        match stmt {
+            Stmt::Print { values, .. } => {
+                self.parenthesize("print", &values.iter().collect::<Vec<_>>())
+            }
+            Stmt::Expression { expr, .. } => self.parenthesize(";", &[expr]),
+            Stmt::Var {
+                name, initializer, ..
+            } => self.parenthesize(
+                &format!("var {}", name),
+                &initializer.iter().collect::<Vec<_>>(),
+            ),
+        }
+    }
+
+    fn visit_expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Literal(literal) => self.output.push_str(&literal.to_string()),
+            Expr::Grouping(expr) => self.parenthesize("group", &[expr]),
+            Expr::Unary {
+                operator, right, ..
+            } => self.parenthesize(&operator.to_string(), &[right]),
+            Expr::Binary {
+                left,
+                operator,
+                right,
+                ..
+            } => self.parenthesize(&operator.to_string(), &[left, right]),
+            Expr::Logical {
+                left,
+                operator,
+                right,
+            } => self.parenthesize(&operator.to_string(), &[left, right]),
+            Expr::Variable { name, .. } => self.output.push_str(name),
+            Expr::Assign { name, value, .. } => self.parenthesize(&format!("= {}", name), &[value]),
+        }
+    }
+}
+
+impl Display for Expr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut printer = AstPrinter::default();
+        printer.visit_expr(self);
+        f.write_str(&printer.output)
+    }
+}
+
+impl Display for Stmt {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut printer = AstPrinter::default();
+        printer.visit_stmt(self);
+        f.write_str(&printer.output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanner::Scanner;
+
+    fn parse(source: &str) -> String {
+        let mut scanner = Scanner::new(source.to_string());
+        scanner.scan_tokens();
+        match Parser::new(scanner.tokens()).parse_expression() {
+            Ok(expr) => expr.to_string(),
+            Err(error) => panic!("{} failed to parse: {}", source, error.message),
+        }
+    }
+
+    #[test]
+    fn literals_print_in_canonical_form() {
+        assert_eq!(parse("true"), "true");
+        assert_eq!(parse("false"), "false");
+        assert_eq!(parse("nil"), "nil");
+        assert_eq!(parse("10"), "10.0");
+        assert_eq!(parse("10.25"), "10.25");
+        assert_eq!(parse("\"hello world\""), "hello world");
+    }
+
+    #[test]
+    fn expressions_print_in_parenthesized_form() {
+        assert_eq!(parse("-3 * (4.5)"), "(* (- 3.0) (group 4.5))");
+        assert_eq!(
+            parse("1 + 2 * 3 == !false"),
+            "(== (+ 1.0 (* 2.0 3.0)) (! false))"
+        );
+    }
+
+    #[test]
+    fn leftover_tokens_are_a_parse_error() {
+        let mut scanner = Scanner::new("1 2".to_string());
+        scanner.scan_tokens();
+        let error = Parser::new(scanner.tokens())
+            .parse_expression()
+            .expect_err("trailing tokens should not parse");
+        assert_eq!(error.location, " at '2'");
+        assert_eq!(error.message, "Expect end of expression.");
+    }
+}
@@ -0,0 +1,76 @@
+use crate::scanner::Scanner;
+use std::io::{self, BufRead, Write};
+
+/// Reads Lox source line by line from `input` until EOF, writing the tokens
+/// of each line to `output`. Line numbers keep counting across inputs, and
+/// lexical errors go to `errors` without ending the session.
+pub fn run(
+    mut input: impl BufRead,
+    mut output: impl Write,
+    mut errors: impl Write,
+    color: bool,
+) -> io::Result<()> {
+    let mut line = 1;
+    loop {
+        write!(output, "> ")?;
+        output.flush()?;
+
+        let mut source = String::new();
+        if input.read_line(&mut source)? == 0 {
+            return Ok(());
+        }
+
+        let mut scanner = Scanner::new(source).starting_at_line(line);
+        scanner.scan_tokens();
+        scanner.write_errors(&mut errors, color)?;
+        for token in scanner.tokens() {
+            writeln!(output, "{}", token)?;
+        }
+        if let Some(eof) = scanner.tokens().last() {
+            line = eof.line();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn each_line_is_tokenized_until_eof() {
+        let mut output = Vec::new();
+        run(Cursor::new("1 +\n\"a\"\n"), &mut output, io::sink(), false).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            concat!(
+                "> NUMBER 1 1.0\nPLUS + null\nEOF  null\n",
+                "> STRING \"a\" a\nEOF  null\n",
+                "> "
+            )
+        );
+    }
+
+    #[test]
+    fn lexical_errors_do_not_end_the_session() {
+        let mut output = Vec::new();
+        let mut errors = Vec::new();
+        run(Cursor::new("1\n@\n2 #\n"), &mut output, &mut errors, false).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            concat!(
+                "> NUMBER 1 1.0\nEOF  null\n",
+                "> EOF  null\n",
+                "> NUMBER 2 2.0\nEOF  null\n",
+                "> "
+            )
+        );
+        assert_eq!(
+            String::from_utf8(errors).unwrap(),
+            concat!(
+                "[line 2] Error: Unexpected character: @\n",
+                "[line 3] Error: Unexpected character: #\n"
+            )
+        );
+    }
+}
@@ -1,5 +1,14 @@
+use crate::color::{paint, CYAN, RED};
+use crate::number::format_number_literal;
+use std::borrow::Cow;
 use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io;
+use std::ops::Range;
+use std::path::Path;
+use std::sync::mpsc::Sender;
 
+#[derive(Debug, Clone, PartialEq)]
 pub enum TokenType {
     /// `(` Left parenthesis
     LeftParen,
@@ -34,216 +43,868 @@ pub enum TokenType {
     Greater,
     GreaterEqual,
     Slash,
+    /// Number literal, with its value normalized to a float as its literal
+    Number,
+    /// `"..."` String literal, with the unquoted contents as its literal
+    String,
+    /// Variable, function or class name
+    Identifier,
+    /// `and` keyword
+    And,
+    /// `class` keyword
+    Class,
+    /// `else` keyword
+    Else,
+    /// `false` keyword
+    False,
+    /// `for` keyword
+    For,
+    /// `fun` keyword
+    Fun,
+    /// `if` keyword
+    If,
+    /// `nil` keyword
+    Nil,
+    /// `or` keyword
+    Or,
+    /// `print` keyword
+    Print,
+    /// `return` keyword
+    Return,
+    /// `super` keyword
+    Super,
+    /// `this` keyword
+    This,
+    /// `true` keyword
+    True,
+    /// `var` keyword
+    Var,
+    /// `while` keyword
+    While,
+    /// `// ...` or `/* ... */` comment text, only produced when comments are kept
+    Comment(String),
+    /// Run of whitespace, only produced when whitespace is emitted
+    Whitespace,
     /// End of string
     Eof,
     UnknownToken(String),
+    /// A string literal that reached the end of input without a closing `"`
+    UnterminatedString,
+    /// A string literal containing a backslash escape other than `\n`, `\t`, `\"` or `\\`
+    InvalidEscapeSequence,
+    /// A `/*` comment that reached the end of input without its closing `*/`
+    UnterminatedBlockComment,
+    /// A string, number or identifier cut short for exceeding the maximum token length
+    TokenTooLong,
 }
 
-struct Token {
+/// High-level grouping of token types, independent of the exact token.
+pub enum TokenCategory {
+    /// Arithmetic, comparison and assignment operators
+    Operator,
+    /// Reserved words such as `var` or `while`
+    Keyword,
+    /// Number and string literals
+    Literal,
+    /// User-chosen names
+    Identifier,
+    /// Delimiters and separators such as parentheses, braces and `;`
+    Punctuation,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Token {
     token_type: TokenType,
-    lexeme: String,
+    /// Borrowed for tokens whose text is fixed, such as operators and keywords
+    lexeme: Cow<'static, str>,
     literal: Option<String>,
-    #[allow(unused)]
-    line: i32,
+    /// Line the token ends on, which is what Lox error messages report
+    line: usize,
+    /// Line of the token's first character; differs from `line` for multi-line strings
+    start_line: usize,
+    /// 1-based column of the token's first character
+    column: usize,
 }
 
-struct LoxError {
-    line: i32,
+#[derive(Debug, thiserror::Error)]
+#[error("[line {line}] Error: {message}")]
+pub struct LoxError {
+    /// The error marker the scanner produced, e.g. `UnknownToken` or `UnterminatedString`
+    kind: TokenType,
+    line: usize,
+    column: usize,
     message: String,
 }
 
+/// Optional scanner behaviors. The default drops comments and whitespace.
+#[derive(Default)]
+pub struct ScannerConfig {
+    /// Emit `Comment` tokens instead of skipping comments
+    pub keep_comments: bool,
+    /// Emit `Whitespace` tokens for runs of spaces, tabs and newlines
+    pub emit_whitespace: bool,
+}
+
 pub struct Scanner {
     source: String,
+    config: ScannerConfig,
     tokens: Vec<Token>,
-    start: i32,
-    current: i32,
-    line: i32,
+    start: usize,
+    current: usize,
+    line: usize,
+    /// Column of the character at `current`
+    column: usize,
+    /// Column of the character at `start`
+    start_column: usize,
+    /// Line of the character at `start`, where errors spanning lines are reported
+    start_line: usize,
     errors: Option<Vec<LoxError>>,
+    max_token_length: Option<usize>,
+    /// Start of the trivia preceding the next token
+    trivia_start: usize,
+    reached_eof: bool,
 }
 
 impl LoxError {
-    fn new(line: i32, error: String) -> Self {
+    fn new(kind: TokenType, line: usize, column: usize, error: String) -> Self {
         Self {
+            kind,
             line,
+            column,
             message: error,
         }
     }
+
+    pub fn kind(&self) -> &TokenType {
+        &self.kind
+    }
+
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl TokenType {
+    /// Returns the category of this token type, or `None` for tokens that
+    /// don't belong to the source text (`Eof`, unknown characters).
+    pub fn category(&self) -> Option<TokenCategory> {
+        match self {
+            TokenType::LeftParen
+            | TokenType::RightParen
+            | TokenType::LeftBrace
+            | TokenType::RightBrace
+            | TokenType::Comma
+            | TokenType::Dot
+            | TokenType::Semicolon => Some(TokenCategory::Punctuation),
+            TokenType::Minus
+            | TokenType::Plus
+            | TokenType::Star
+            | TokenType::Equal
+            | TokenType::EqualEqual
+            | TokenType::Bang
+            | TokenType::BangEqual
+            | TokenType::Less
+            | TokenType::LessEqual
+            | TokenType::Greater
+            | TokenType::GreaterEqual
+            | TokenType::Slash => Some(TokenCategory::Operator),
+            TokenType::Number | TokenType::String => Some(TokenCategory::Literal),
+            TokenType::Identifier => Some(TokenCategory::Identifier),
+            TokenType::And
+            | TokenType::Class
+            | TokenType::Else
+            | TokenType::False
+            | TokenType::For
+            | TokenType::Fun
+            | TokenType::If
+            | TokenType::Nil
+            | TokenType::Or
+            | TokenType::Print
+            | TokenType::Return
+            | TokenType::Super
+            | TokenType::This
+            | TokenType::True
+            | TokenType::Var
+            | TokenType::While => Some(TokenCategory::Keyword),
+            TokenType::Comment(_)
+            | TokenType::Whitespace
+            | TokenType::Eof
+            | TokenType::UnknownToken(_)
+            | TokenType::UnterminatedString
+            | TokenType::InvalidEscapeSequence
+            | TokenType::UnterminatedBlockComment
+            | TokenType::TokenTooLong => None,
+        }
+    }
+
+    /// The source text of tokens that can only be spelled one way, which
+    /// `make_token` borrows instead of copying out of the source.
+    fn fixed_lexeme(&self) -> Option<&'static str> {
+        let text = match self {
+            TokenType::LeftParen => "(",
+            TokenType::RightParen => ")",
+            TokenType::LeftBrace => "{",
+            TokenType::RightBrace => "}",
+            TokenType::Comma => ",",
+            TokenType::Dot => ".",
+            TokenType::Minus => "-",
+            TokenType::Plus => "+",
+            TokenType::Semicolon => ";",
+            TokenType::Star => "*",
+            TokenType::Equal => "=",
+            TokenType::EqualEqual => "==",
+            TokenType::Bang => "!",
+            TokenType::BangEqual => "!=",
+            TokenType::Less => "<",
+            TokenType::LessEqual => "<=",
+            TokenType::Greater => ">",
+            TokenType::GreaterEqual => ">=",
+            TokenType::Slash => "/",
+            TokenType::And => "and",
+            TokenType::Class => "class",
+            TokenType::Else => "else",
+            TokenType::False => "false",
+            TokenType::For => "for",
+            TokenType::Fun => "fun",
+            TokenType::If => "if",
+            TokenType::Nil => "nil",
+            TokenType::Or => "or",
+            TokenType::Print => "print",
+            TokenType::Return => "return",
+            TokenType::Super => "super",
+            TokenType::This => "this",
+            TokenType::True => "true",
+            TokenType::Var => "var",
+            TokenType::While => "while",
+            _ => return None,
+        };
+        Some(text)
+    }
 }
 
 impl Token {
-    pub fn new(token_type: TokenType, lexeme: String, literal: Option<String>, line: i32) -> Self {
+    pub fn new(
+        token_type: TokenType,
+        lexeme: String,
+        literal: Option<String>,
+        line: usize,
+        column: usize,
+    ) -> Self {
         Self {
             token_type,
-            lexeme,
+            lexeme: Cow::Owned(lexeme),
             literal,
             line,
+            start_line: line,
+            column,
         }
     }
 
-    pub fn to_string(&self) -> String {
-        match &self.literal {
-            None => format!("{} {} null", self.token_type, self.lexeme),
-            Some(literal) => format!("{} {} {}", self.token_type, self.lexeme, literal),
-        }
+    pub fn token_type(&self) -> &TokenType {
+        &self.token_type
+    }
+
+    pub fn lexeme(&self) -> &str {
+        &self.lexeme
+    }
+
+    pub fn literal(&self) -> Option<&str> {
+        self.literal.as_deref()
+    }
+
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    pub fn start_line(&self) -> usize {
+        self.start_line
+    }
+
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    /// Formats the token like `Display`, prefixed with the `line:column` of its first character.
+    pub fn to_string_with_position(&self) -> String {
+        format!("{}:{} {}", self.start_line, self.column, self)
     }
 }
 
 impl Scanner {
     pub fn new(source: String) -> Self {
+        Self::with_config(source, ScannerConfig::default())
+    }
+
+    pub fn with_config(source: String, config: ScannerConfig) -> Self {
         Self {
             source,
+            config,
             tokens: Vec::new(),
             start: 0,
             current: 0,
             line: 1,
+            column: 1,
+            start_column: 1,
+            start_line: 1,
             errors: None,
+            max_token_length: None,
+            trivia_start: 0,
+            reached_eof: false,
         }
     }
 
+    /// Replaces the source and clears all scan state, keeping the configuration
+    /// and the capacity of the token buffer for the next scan.
+    pub fn reset(&mut self, source: String) {
+        self.source = source;
+        self.tokens.clear();
+        self.errors = None;
+        self.start = 0;
+        self.current = 0;
+        self.line = 1;
+        self.column = 1;
+        self.start_column = 1;
+        self.start_line = 1;
+        self.trivia_start = 0;
+        self.reached_eof = false;
+    }
+
+    /// Reads the file at `path` into a new scanner.
+    pub fn from_file(path: &Path) -> io::Result<Self> {
+        fs::read_to_string(path).map(Self::new)
+    }
+
+    /// Numbers lines from `line` instead of 1, for sources that continue earlier input.
+    pub fn starting_at_line(mut self, line: usize) -> Self {
+        self.line = line;
+        self
+    }
+
+    /// Rejects any token whose lexeme is longer than `limit` bytes. Strings, numbers
+    /// and identifiers are abandoned as soon as they pass the limit, and nothing
+    /// after an over-long token is scanned, so pathological input fails fast.
+    pub fn with_max_token_length(mut self, limit: usize) -> Self {
+        self.max_token_length = Some(limit);
+        self
+    }
+
     pub fn has_errors(&self) -> bool {
         self.errors.is_some()
     }
 
+    /// Prints every collected lexical error to stderr, ANSI-colored when `color` is set.
+    pub fn report_errors(&self, color: bool) {
+        self.write_errors(io::stderr(), color)
+            .expect("failed printing to stderr");
+    }
+
+    /// Writes every collected lexical error to `output`, one `[line N] Error: ...` per line.
+    pub fn write_errors(&self, mut output: impl io::Write, color: bool) -> io::Result<()> {
+        for error in self.errors() {
+            writeln!(
+                output,
+                "[line {}] {}: {}",
+                paint(&error.line.to_string(), CYAN, color),
+                paint("Error", RED, color),
+                error.message
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Serializes the scanned tokens and errors as a JSON object of the form
+    /// `{"tokens": [{"type", "lexeme", "literal", "line"}, ...], "errors": [{"line", "message"}, ...]}`.
+    pub fn to_json(&self) -> String {
+        let tokens: Vec<String> = self
+            .tokens
+            .iter()
+            .map(|token| {
+                let literal = token
+                    .literal
+                    .as_deref()
+                    .map_or("null".to_string(), json_string);
+                format!(
+                    "{{\"type\":{},\"lexeme\":{},\"literal\":{},\"line\":{}}}",
+                    json_string(&token.token_type.to_string()),
+                    json_string(&token.lexeme),
+                    literal,
+                    token.line
+                )
+            })
+            .collect();
+        let errors: Vec<String> = self
+            .errors()
+            .iter()
+            .map(|error| {
+                format!(
+                    "{{\"line\":{},\"message\":{}}}",
+                    error.line,
+                    json_string(&error.message)
+                )
+            })
+            .collect();
+        format!(
+            "{{\"tokens\":[{}],\"errors\":[{}]}}",
+            tokens.join(","),
+            errors.join(",")
+        )
+    }
+
+    /// Forgets the errors for unexpected characters, keeping every other lexical error.
+    pub fn discard_unexpected_characters(&mut self) {
+        if let Some(errors) = &mut self.errors {
+            errors.retain(|error| !matches!(error.kind, TokenType::UnknownToken(_)));
+            if errors.is_empty() {
+                self.errors = None;
+            }
+        }
+    }
+
+    /// Returns the lexical errors found so far, in source order.
+    pub fn errors(&self) -> &[LoxError] {
+        self.errors.as_deref().unwrap_or(&[])
+    }
+
+    /// Returns the scanned tokens, ending with `Eof` once `scan_tokens` has run.
+    pub fn tokens(&self) -> &[Token] {
+        &self.tokens
+    }
+
     fn is_at_end(&self) -> bool {
-        self.current >= self.source.len() as i32
+        self.current >= self.source.len()
     }
 
-    fn advance(&mut self) {
-        self.current += 1;
+    fn exceeds_max_length(&self) -> bool {
+        self.max_token_length
+            .is_some_and(|limit| self.current - self.start > limit)
+    }
+
+    /// Consumes and returns the character at `current`, which may span several bytes.
+    fn advance(&mut self) -> Option<char> {
+        let next = self.peek()?;
+        self.current += next.len_utf8();
+        if next == '\n' {
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(next)
     }
 
     fn current_char(&self) -> &str {
-        &self.source.as_str()[(self.start as usize)..(self.current as usize)]
+        &self.source.as_str()[self.start..self.current]
     }
 
-    fn add_token(&mut self, token_type: TokenType, literal: Option<String>) {
-        let text = self
-            .source
-            .get(self.start as usize..(self.current as usize))
-            .unwrap_or("");
-        self.tokens
-            .push(Token::new(token_type, text.to_string(), literal, self.line))
+    fn make_token(&self, token_type: TokenType, literal: Option<String>) -> Token {
+        let lexeme = match token_type.fixed_lexeme() {
+            Some(text) => Cow::Borrowed(text),
+            None => Cow::Owned(
+                self.source
+                    .get(self.start..self.current)
+                    .unwrap_or("")
+                    .to_string(),
+            ),
+        };
+        Token {
+            token_type,
+            lexeme,
+            literal,
+            line: self.line,
+            start_line: self.start_line,
+            column: self.start_column,
+        }
     }
 
     pub fn scan_tokens(&mut self) {
+        // Taken out for the duration of the scan so a buffer kept by `reset` is reused.
+        let mut tokens = std::mem::take(&mut self.tokens);
+        let errors = self.scan_each(|token, _| tokens.push(token));
+        self.tokens = tokens;
+
+        if !errors.is_empty() {
+            self.errors = Some(errors);
+        }
+    }
+
+    /// Scans the source like `scan_tokens`, additionally returning the trivia
+    /// (whitespace, comments and rejected characters) that precedes each token.
+    /// Concatenating every trivia with its token's lexeme reproduces the source.
+    pub fn tokens_with_trivia(&mut self) -> Vec<(&str, &Token)> {
+        let mut tokens = std::mem::take(&mut self.tokens);
+        let first = tokens.len();
+        let mut spans = Vec::new();
+        let errors = self.scan_each(|token, trivia| {
+            tokens.push(token);
+            spans.push(trivia);
+        });
+        self.tokens = tokens;
+
+        if !errors.is_empty() {
+            self.errors = Some(errors);
+        }
+
+        spans
+            .into_iter()
+            .zip(&self.tokens[first..])
+            .map(|(trivia, token)| (&self.source[trivia], token))
+            .collect()
+    }
+
+    /// Scans the source, sending every token (including the final `Eof`) through `tx`
+    /// as soon as it is produced, so a consumer on another thread can start early.
+    /// Lexical errors are returned once scanning is complete.
+    pub fn scan_into(mut self, tx: Sender<Token>) -> Result<(), Vec<LoxError>> {
+        // A closed receiver only means the consumer stopped listening.
+        let errors = self.scan_each(|token, _| {
+            let _ = tx.send(token);
+        });
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Lazily scans the source, yielding each token (ending with `Eof`) or
+    /// lexical error as soon as it is found. Errors are not recorded in the scanner.
+    pub fn iter_tokens(&mut self) -> impl Iterator<Item = Result<Token, LoxError>> + '_ {
+        std::iter::from_fn(move || {
+            self.scan_next()
+                .map(|scanned| scanned.map(|(token, _)| token))
+        })
+    }
+
+    /// Runs the scan loop, handing each token and the byte span of its leading
+    /// trivia to `emit`, and collecting errors.
+    fn scan_each(&mut self, mut emit: impl FnMut(Token, Range<usize>)) -> Vec<LoxError> {
         let mut errors: Vec<LoxError> = Vec::new();
+        while let Some(scanned) = self.scan_next() {
+            match scanned {
+                Ok((token, trivia)) => emit(token, trivia),
+                Err(error) => errors.push(error),
+            }
+        }
+        errors
+    }
 
+    /// Scans up to the next token or error, returning the token along with the
+    /// byte span of its leading trivia. Returns `None` once `Eof` has been produced.
+    fn scan_next(&mut self) -> Option<Result<(Token, Range<usize>), LoxError>> {
         while !self.is_at_end() {
             self.start = self.current;
-            let next_token = self.scan_token();
-            if next_token.is_none() {
+            self.start_column = self.column;
+            self.start_line = self.line;
+            let Some((mut token_type, literal)) = self.scan_token() else {
                 continue;
-            }
-            let (token_type, literal) = next_token.unwrap();
-            if let TokenType::UnknownToken(unknown_token) = token_type {
-                let error = LoxError::new(
-                    self.line.clone(),
-                    format!("Unexpected character: {}", unknown_token),
-                );
-                errors.push(error);
-            } else {
-                self.add_token(token_type, literal);
-            }
+            };
+            let error = match &token_type {
+                TokenType::UnknownToken(unknown_token) => {
+                    format!("Unexpected character: {}", unknown_token)
+                }
+                TokenType::UnterminatedString => "Unterminated string.".to_string(),
+                TokenType::InvalidEscapeSequence => "Invalid escape sequence.".to_string(),
+                TokenType::UnterminatedBlockComment => "Unterminated block comment.".to_string(),
+                _ if token_type == TokenType::TokenTooLong || self.exceeds_max_length() => {
+                    token_type = TokenType::TokenTooLong;
+                    self.current = self.source.len();
+                    "Token exceeds maximum length.".to_string()
+                }
+                _ => {
+                    let trivia = self.trivia_start..self.start;
+                    self.trivia_start = self.current;
+                    return Some(Ok((self.make_token(token_type, literal), trivia)));
+                }
+            };
+            return Some(Err(LoxError::new(
+                token_type,
+                self.start_line,
+                self.start_column,
+                error,
+            )));
         }
 
-        self.tokens
-            .push(Token::new(TokenType::Eof, "".to_string(), None, self.line));
-
-        if !errors.is_empty() {
-            self.errors = Some(errors);
+        if self.reached_eof {
+            return None;
         }
+        self.reached_eof = true;
+        Some(Ok((
+            Token::new(TokenType::Eof, "".to_string(), None, self.line, self.column),
+            self.trivia_start..self.current,
+        )))
     }
 
     fn scan_token(&mut self) -> Option<(TokenType, Option<String>)> {
-        self.advance();
-        let result = match self.current_char() {
-            "(" => (TokenType::LeftParen, None),
-            ")" => (TokenType::RightParen, None),
-            "{" => (TokenType::LeftBrace, None),
-            "}" => (TokenType::RightBrace, None),
-            "," => (TokenType::Comma, None),
-            "." => (TokenType::Dot, None),
-            "-" => (TokenType::Minus, None),
-            "+" => (TokenType::Plus, None),
-            ";" => (TokenType::Semicolon, None),
-            "*" => (TokenType::Star, None),
-            "=" => {
+        let c = self.advance()?;
+        let result = match c {
+            '(' => (TokenType::LeftParen, None),
+            ')' => (TokenType::RightParen, None),
+            '{' => (TokenType::LeftBrace, None),
+            '}' => (TokenType::RightBrace, None),
+            ',' => (TokenType::Comma, None),
+            '.' => (TokenType::Dot, None),
+            '-' => (TokenType::Minus, None),
+            '+' => (TokenType::Plus, None),
+            ';' => (TokenType::Semicolon, None),
+            '*' => (TokenType::Star, None),
+            '=' => {
                 if self.matches_next("=") {
                     (TokenType::EqualEqual, None)
                 } else {
                     (TokenType::Equal, None)
                 }
             }
-            "!" => {
+            '!' => {
                 if self.matches_next("=") {
                     (TokenType::BangEqual, None)
                 } else {
                     (TokenType::Bang, None)
                 }
             }
-            "<" => {
+            '<' => {
                 if self.matches_next("=") {
                     (TokenType::LessEqual, None)
                 } else {
                     (TokenType::Less, None)
                 }
             }
-            ">" => {
+            '>' => {
                 if self.matches_next("=") {
                     (TokenType::GreaterEqual, None)
                 } else {
                     (TokenType::Greater, None)
                 }
             }
-            "/" => {
+            '/' => {
                 if self.matches_next("/") {
-                    // while self.source.as_str().chars().nth(self.current as usize).unwrap() != '\n' && !self.is_at_end() {
-                    while !self.is_at_end()
-                        && self
-                            .source
-                            .as_bytes()
-                            .iter()
-                            .nth(self.current as usize)
-                            .unwrap()
-                            != &b'\n'
-                    {
+                    while self.peek().is_some_and(|c| c != '\n') {
                         self.advance();
                     }
-                    return None;
+                    return self.comment(2, 0);
+                } else if self.matches_next("*") {
+                    return self.block_comment();
                 } else {
                     (TokenType::Slash, None)
                 }
             }
-            " " | "\r" | "\t" => return None,
-            "\n" => {
-                self.line += 1;
-                return None;
+            '"' => self.string(),
+            c if c.is_ascii_digit() => self.number(),
+            c if is_identifier_start(c) => self.identifier(),
+            ' ' | '\r' | '\t' | '\n' => {
+                if c == '\n' {
+                    self.line += 1;
+                }
+                if !self.config.emit_whitespace {
+                    return None;
+                }
+                self.whitespace()
             }
-            ch => (TokenType::UnknownToken(ch.to_string()), None),
+            c => (TokenType::UnknownToken(c.to_string()), None),
         };
 
         Some(result)
     }
 
-    fn matches_next(&mut self, expected: &str) -> bool {
+    /// Scans a string literal, unescaping `\n`, `\t`, `\"` and `\\` into its value.
+    fn string(&mut self) -> (TokenType, Option<String>) {
+        let mut value = String::new();
+        let mut invalid_escape = false;
+        while let Some(c) = self.peek().filter(|&c| c != '"') {
+            if self.exceeds_max_length() {
+                return (TokenType::TokenTooLong, None);
+            }
+            self.advance();
+            match c {
+                '\\' => match self.advance() {
+                    Some('n') => value.push('\n'),
+                    Some('t') => value.push('\t'),
+                    Some('"') => value.push('"'),
+                    Some('\\') => value.push('\\'),
+                    Some(other) => {
+                        if other == '\n' {
+                            self.line += 1;
+                        }
+                        invalid_escape = true;
+                    }
+                    None => {}
+                },
+                '\n' => {
+                    self.line += 1;
+                    value.push(c);
+                }
+                c => value.push(c),
+            }
+        }
+
         if self.is_at_end() {
-            return false;
-        };
-        match self
-            .source
-            .get((self.current as usize)..(self.current as usize + 1))
-        {
-            None => false,
-            Some(next_char) => {
-                if next_char != expected {
-                    false
-                } else {
-                    self.current += 1;
-                    true
+            return (TokenType::UnterminatedString, None);
+        }
+
+        // The closing quote.
+        self.advance();
+        if invalid_escape {
+            return (TokenType::InvalidEscapeSequence, None);
+        }
+        (TokenType::String, Some(value))
+    }
+
+    /// Skips a `/* ... */` comment whose opening `/*` was already consumed,
+    /// honouring nested comments. Only produces a token when the comment is unterminated.
+    fn block_comment(&mut self) -> Option<(TokenType, Option<String>)> {
+        let mut depth = 1;
+        while depth > 0 {
+            if self.is_at_end() {
+                return Some((TokenType::UnterminatedBlockComment, None));
+            }
+            if self.matches_next("/*") {
+                depth += 1;
+            } else if self.matches_next("*/") {
+                depth -= 1;
+            } else {
+                if self.peek() == Some('\n') {
+                    self.line += 1;
+                }
+                self.advance();
+            }
+        }
+        self.comment(2, 2)
+    }
+
+    /// Produces a `Comment` token for the comment just consumed when comments are
+    /// kept, its text being the lexeme without the opening and closing delimiters.
+    fn comment(&self, opening: usize, closing: usize) -> Option<(TokenType, Option<String>)> {
+        if !self.config.keep_comments {
+            return None;
+        }
+        let text = &self.source[self.start + opening..self.current - closing];
+        Some((TokenType::Comment(text.to_string()), None))
+    }
+
+    /// Consumes the rest of a whitespace run whose first character was already read.
+    fn whitespace(&mut self) -> (TokenType, Option<String>) {
+        while let Some(c @ (' ' | '\r' | '\t' | '\n')) = self.peek() {
+            if c == '\n' {
+                self.line += 1;
+            }
+            self.advance();
+        }
+        (TokenType::Whitespace, None)
+    }
+
+    fn number(&mut self) -> (TokenType, Option<String>) {
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            if self.exceeds_max_length() {
+                return (TokenType::TokenTooLong, None);
+            }
+            self.advance();
+        }
+
+        // A fractional part needs at least one digit after the dot.
+        if self.peek() == Some('.') && self.peek_next().is_some_and(|c| c.is_ascii_digit()) {
+            self.advance();
+            while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+                if self.exceeds_max_length() {
+                    return (TokenType::TokenTooLong, None);
                 }
+                self.advance();
+            }
+        }
+
+        let value: f64 = self
+            .current_char()
+            .parse()
+            .expect("Number lexeme should only contain digits and a dot");
+        (TokenType::Number, Some(format_number_literal(value)))
+    }
+
+    fn identifier(&mut self) -> (TokenType, Option<String>) {
+        while self
+            .peek()
+            .is_some_and(|c| is_identifier_start(c) || c.is_ascii_digit())
+        {
+            if self.exceeds_max_length() {
+                return (TokenType::TokenTooLong, None);
             }
+            self.advance();
         }
+
+        let token_type = keyword(self.current_char()).unwrap_or(TokenType::Identifier);
+        (token_type, None)
     }
+
+    /// Returns the character at `current` without consuming it.
+    fn peek(&self) -> Option<char> {
+        self.source[self.current..].chars().next()
+    }
+
+    /// Returns the character after the one at `current` without consuming anything.
+    fn peek_next(&self) -> Option<char> {
+        self.source[self.current..].chars().nth(1)
+    }
+
+    fn matches_next(&mut self, expected: &str) -> bool {
+        if !self.source[self.current..].starts_with(expected) {
+            return false;
+        }
+        self.current += expected.len();
+        self.column += expected.chars().count();
+        true
+    }
+}
+
+fn is_identifier_start(c: char) -> bool {
+    c.is_ascii_alphabetic() || c == '_'
+}
+
+/// Quotes `text` as a JSON string literal.
+fn json_string(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if (c as u32) < 0x20 => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Maps reserved words to their keyword token type.
+fn keyword(text: &str) -> Option<TokenType> {
+    let token_type = match text {
+        "and" => TokenType::And,
+        "class" => TokenType::Class,
+        "else" => TokenType::Else,
+        "false" => TokenType::False,
+        "for" => TokenType::For,
+        "fun" => TokenType::Fun,
+        "if" => TokenType::If,
+        "nil" => TokenType::Nil,
+        "or" => TokenType::Or,
+        "print" => TokenType::Print,
+        "return" => TokenType::Return,
+        "super" => TokenType::Super,
+        "this" => TokenType::This,
+        "true" => TokenType::True,
+        "var" => TokenType::Var,
+        "while" => TokenType::While,
+        _ => return None,
+    };
+    Some(token_type)
 }
 
 impl Display for TokenType {
@@ -268,22 +929,536 @@ impl Display for TokenType {
             TokenType::Greater => write!(f, "GREATER"),
             TokenType::GreaterEqual => write!(f, "GREATER_EQUAL"),
             TokenType::Slash => write!(f, "SLASH"),
+            TokenType::Number => write!(f, "NUMBER"),
+            TokenType::String => write!(f, "STRING"),
+            TokenType::Identifier => write!(f, "IDENTIFIER"),
+            TokenType::And => write!(f, "AND"),
+            TokenType::Class => write!(f, "CLASS"),
+            TokenType::Else => write!(f, "ELSE"),
+            TokenType::False => write!(f, "FALSE"),
+            TokenType::For => write!(f, "FOR"),
+            TokenType::Fun => write!(f, "FUN"),
+            TokenType::If => write!(f, "IF"),
+            TokenType::Nil => write!(f, "NIL"),
+            TokenType::Or => write!(f, "OR"),
+            TokenType::Print => write!(f, "PRINT"),
+            TokenType::Return => write!(f, "RETURN"),
+            TokenType::Super => write!(f, "SUPER"),
+            TokenType::This => write!(f, "THIS"),
+            TokenType::True => write!(f, "TRUE"),
+            TokenType::Var => write!(f, "VAR"),
+            TokenType::While => write!(f, "WHILE"),
+            TokenType::Comment(_) => write!(f, "COMMENT"),
+            TokenType::Whitespace => write!(f, "WHITESPACE"),
             TokenType::Eof => write!(f, "EOF"),
             TokenType::UnknownToken(message) => write!(f, "Unknown token {}", message),
+            TokenType::UnterminatedString => write!(f, "Unterminated string"),
+            TokenType::InvalidEscapeSequence => write!(f, "Invalid escape sequence"),
+            TokenType::UnterminatedBlockComment => write!(f, "Unterminated block comment"),
+            TokenType::TokenTooLong => write!(f, "Token too long"),
+        }
+    }
+}
+
+impl Display for Token {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.literal {
+            None => write!(f, "{} {} null", self.token_type, self.lexeme),
+            Some(literal) => write!(f, "{} {} {}", self.token_type, self.lexeme, literal),
+        }
+    }
+}
+
+impl Display for TokenCategory {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TokenCategory::Operator => write!(f, "operator"),
+            TokenCategory::Keyword => write!(f, "keyword"),
+            TokenCategory::Literal => write!(f, "literal"),
+            TokenCategory::Identifier => write!(f, "identifier"),
+            TokenCategory::Punctuation => write!(f, "punctuation"),
         }
     }
 }
 
 impl Display for Scanner {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        if let Some(errors) = &self.errors {
-            errors.iter().for_each(|error| {
-                eprintln!("[line {}] Error: {}", error.line, error.message);
-            })
+        for token in &self.tokens {
+            writeln!(f, "{}", token)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scan(source: &str) -> Scanner {
+        let mut scanner = Scanner::new(source.to_string());
+        scanner.scan_tokens();
+        scanner
+    }
+
+    fn token_types(source: &str) -> Vec<TokenType> {
+        scan(source)
+            .tokens()
+            .iter()
+            .map(|token| token.token_type().clone())
+            .collect()
+    }
+
+    #[test]
+    fn mistyped_operators_split_into_single_character_tokens() {
+        assert_eq!(
+            token_types("=<"),
+            [TokenType::Equal, TokenType::Less, TokenType::Eof]
+        );
+        assert_eq!(
+            token_types("<>"),
+            [TokenType::Less, TokenType::Greater, TokenType::Eof]
+        );
+        assert_eq!(
+            token_types("!!"),
+            [TokenType::Bang, TokenType::Bang, TokenType::Eof]
+        );
+    }
+
+    #[test]
+    fn two_character_operators_scan_as_one_token() {
+        assert_eq!(token_types("=="), [TokenType::EqualEqual, TokenType::Eof]);
+        assert_eq!(token_types("!="), [TokenType::BangEqual, TokenType::Eof]);
+        assert_eq!(token_types("<="), [TokenType::LessEqual, TokenType::Eof]);
+        assert_eq!(token_types(">="), [TokenType::GreaterEqual, TokenType::Eof]);
+    }
+
+    #[test]
+    fn newlines_are_skipped_and_counted() {
+        let scanner = scan("(\n)\n");
+        let tokens = scanner.tokens();
+        assert_eq!(
+            token_types("(\n)\n"),
+            [TokenType::LeftParen, TokenType::RightParen, TokenType::Eof]
+        );
+        assert_eq!(tokens[1].line(), 2);
+        assert_eq!(tokens[2].line(), 3);
+        assert!(!scanner.has_errors());
+    }
+
+    #[test]
+    fn line_comments_produce_no_tokens() {
+        assert_eq!(
+            token_types("// hello\n*"),
+            [TokenType::Star, TokenType::Eof]
+        );
+    }
+
+    #[test]
+    fn line_comment_may_end_the_input_without_a_newline() {
+        let scanner = scan("+ // trailing");
+        assert_eq!(
+            token_types("+ // trailing"),
+            [TokenType::Plus, TokenType::Eof]
+        );
+        assert!(!scanner.has_errors());
+    }
+
+    #[test]
+    fn errors_report_the_line_of_the_offending_character() {
+        let scanner = scan("*\n\n@");
+        assert_eq!(scanner.errors().len(), 1);
+        assert_eq!(scanner.errors()[0].line(), 3);
+        assert_eq!(scanner.errors()[0].message(), "Unexpected character: @");
+
+        let scanner = scan("@\n#\n\n$");
+        let lines: Vec<usize> = scanner.errors().iter().map(LoxError::line).collect();
+        assert_eq!(lines, [1, 2, 4]);
+    }
+
+    #[test]
+    fn each_unexpected_character_is_its_own_error() {
+        let scanner = scan("(@#$)");
+        let messages: Vec<&str> = scanner.errors().iter().map(LoxError::message).collect();
+        assert_eq!(
+            messages,
+            [
+                "Unexpected character: @",
+                "Unexpected character: #",
+                "Unexpected character: $",
+            ]
+        );
+        assert_eq!(
+            token_types("(@#$)"),
+            [TokenType::LeftParen, TokenType::RightParen, TokenType::Eof]
+        );
+    }
+
+    #[test]
+    fn lone_slash_is_a_slash_token() {
+        assert_eq!(token_types("/"), [TokenType::Slash, TokenType::Eof]);
+        assert_eq!(scan("/").tokens()[0].to_string(), "SLASH / null");
+    }
+
+    #[test]
+    fn double_slash_starts_a_comment() {
+        assert_eq!(token_types("//comment"), [TokenType::Eof]);
+    }
+
+    #[test]
+    fn slash_between_operands_is_division() {
+        assert_eq!(
+            token_types("a/b"),
+            [
+                TokenType::Identifier,
+                TokenType::Slash,
+                TokenType::Identifier,
+                TokenType::Eof
+            ]
+        );
+    }
+
+    #[test]
+    fn eof_line_counts_every_newline() {
+        assert_eq!(scan("\n\n\n").tokens()[0].line(), 4);
+        assert_eq!(scan("1\n2\n").tokens()[2].line(), 3);
+        assert_eq!(scan("1\n2").tokens()[2].line(), 2);
+    }
+
+    #[test]
+    fn over_long_tokens_stop_the_scan() {
+        let mut scanner = Scanner::new("abc abcdefgh + 1".to_string()).with_max_token_length(4);
+        scanner.scan_tokens();
+        assert_eq!(scanner.errors().len(), 1);
+        assert_eq!(
+            scanner.errors()[0].message(),
+            "Token exceeds maximum length."
+        );
+        let types: Vec<&TokenType> = scanner.tokens().iter().map(Token::token_type).collect();
+        assert_eq!(types, [&TokenType::Identifier, &TokenType::Eof]);
+    }
+
+    #[test]
+    fn over_long_strings_are_abandoned_at_the_limit() {
+        // Unterminated, so only an early stop can report the length error.
+        let source = format!("\"{}", "x".repeat(1000));
+        let mut scanner = Scanner::new(source).with_max_token_length(8);
+        scanner.scan_tokens();
+        let messages: Vec<&str> = scanner.errors().iter().map(LoxError::message).collect();
+        assert_eq!(messages, ["Token exceeds maximum length."]);
+    }
+
+    #[test]
+    fn from_file_scans_the_file_contents() {
+        let path = std::env::temp_dir().join(format!(
+            "codecrafters-interpreter-from-file-{}.lox",
+            std::process::id()
+        ));
+        fs::write(&path, "var x;").unwrap();
+        let scanner = Scanner::from_file(&path);
+        fs::remove_file(&path).unwrap();
+
+        let mut scanner = scanner.unwrap();
+        scanner.scan_tokens();
+        let types: Vec<&TokenType> = scanner.tokens().iter().map(Token::token_type).collect();
+        assert_eq!(
+            types,
+            [
+                &TokenType::Var,
+                &TokenType::Identifier,
+                &TokenType::Semicolon,
+                &TokenType::Eof
+            ]
+        );
+    }
+
+    #[test]
+    fn from_file_fails_on_a_missing_path() {
+        assert!(Scanner::from_file(Path::new("/nonexistent/input.lox")).is_err());
+    }
+
+    #[test]
+    fn iter_tokens_scans_only_what_is_consumed() {
+        let mut scanner = Scanner::new("a + b;\n".repeat(10_000));
+        let first: Vec<TokenType> = scanner
+            .iter_tokens()
+            .take(3)
+            .map(|token| token.unwrap().token_type)
+            .collect();
+        assert_eq!(
+            first,
+            [
+                TokenType::Identifier,
+                TokenType::Plus,
+                TokenType::Identifier
+            ]
+        );
+        assert_eq!(scanner.current, "a + b".len());
+        assert!(scanner.tokens().is_empty());
+    }
+
+    #[test]
+    fn trivia_and_lexemes_rebuild_the_source() {
+        let source = "var x = 1; // one\n\t/* two */ print  x @;\n\n";
+        let mut scanner = Scanner::new(source.to_string());
+        let rebuilt: String = scanner
+            .tokens_with_trivia()
+            .into_iter()
+            .map(|(trivia, token)| format!("{}{}", trivia, token.lexeme()))
+            .collect();
+        assert_eq!(rebuilt, source);
+    }
+
+    #[test]
+    fn scan_into_streams_the_same_tokens_as_scan_tokens() {
+        let source = "var total = 1 + 2.5;\nprint \"sum\" + total;";
+        let (tx, rx) = std::sync::mpsc::channel();
+        let scanning = std::thread::spawn(move || Scanner::new(source.to_string()).scan_into(tx));
+        let collecting = std::thread::spawn(move || rx.iter().collect::<Vec<Token>>());
+
+        assert!(scanning.join().unwrap().is_ok());
+        assert_eq!(collecting.join().unwrap(), scan(source).tokens());
+    }
+
+    #[test]
+    fn block_comments_nest() {
+        assert_eq!(
+            token_types("1 /* a /* b */ c */ 2"),
+            [TokenType::Number, TokenType::Number, TokenType::Eof]
+        );
+    }
+
+    #[test]
+    fn unterminated_block_comment_is_an_error() {
+        let scanner = scan("1 /* a /* b */ c");
+        let messages: Vec<&str> = scanner.errors().iter().map(LoxError::message).collect();
+        assert_eq!(messages, ["Unterminated block comment."]);
+        assert_eq!(
+            token_types("1 /* a /* b */ c"),
+            [TokenType::Number, TokenType::Eof]
+        );
+    }
+
+    #[test]
+    fn string_escapes_are_unescaped_in_the_literal() {
+        let scanner = scan(r#""\t" "a\"b""#);
+        let tokens = scanner.tokens();
+        assert_eq!(tokens[0].lexeme(), r#""\t""#);
+        assert_eq!(tokens[0].literal(), Some("\t"));
+        assert_eq!(tokens[1].lexeme(), r#""a\"b""#);
+        assert_eq!(tokens[1].literal(), Some("a\"b"));
+    }
+
+    #[test]
+    fn unknown_escape_is_an_error() {
+        let scanner = scan(r#""\q""#);
+        let messages: Vec<&str> = scanner.errors().iter().map(LoxError::message).collect();
+        assert_eq!(messages, ["Invalid escape sequence."]);
+        assert_eq!(token_types(r#""\q""#), [TokenType::Eof]);
+    }
+
+    #[test]
+    fn unterminated_string_reports_the_line_of_its_opening_quote() {
+        let scanner = scan("1\n\"a\nb\nc");
+        assert_eq!(scanner.errors().len(), 1);
+        assert_eq!(scanner.errors()[0].line(), 2);
+        assert_eq!(scanner.errors()[0].message(), "Unterminated string.");
+    }
+
+    #[test]
+    fn reset_starts_a_clean_scan() {
+        let mut scanner = Scanner::new("@ (\n\"open".to_string());
+        scanner.scan_tokens();
+        assert!(scanner.has_errors());
+
+        scanner.reset("+ -".to_string());
+        scanner.scan_tokens();
+        assert!(!scanner.has_errors());
+        assert_eq!(
+            scanner.to_string(),
+            "PLUS + null\nMINUS - null\nEOF  null\n"
+        );
+        assert!(scanner.tokens().iter().all(|token| token.line() == 1));
+        assert_eq!(scanner.tokens()[1].column(), 3);
+    }
+
+    #[test]
+    fn tokens_report_their_column() {
+        let scanner = scan("var x;\nx = 1 + 2;");
+        let one = &scanner.tokens()[5];
+        assert_eq!(one.lexeme(), "1");
+        assert_eq!((one.line(), one.column()), (2, 5));
+        assert_eq!(one.to_string_with_position(), "2:5 NUMBER 1 1.0");
+    }
+
+    #[test]
+    fn peek_looks_ahead_without_consuming() {
+        let mut scanner = Scanner::new("é!".to_string());
+        assert_eq!(
+            (scanner.peek(), scanner.peek_next()),
+            (Some('é'), Some('!'))
+        );
+        scanner.advance();
+        assert_eq!((scanner.peek(), scanner.peek_next()), (Some('!'), None));
+        scanner.advance();
+        assert_eq!((scanner.peek(), scanner.peek_next()), (None, None));
+        assert_eq!(scanner.advance(), None);
+        assert_eq!((scanner.peek(), scanner.peek_next()), (None, None));
+    }
+
+    #[test]
+    fn to_json_lists_tokens_and_errors() {
+        assert_eq!(
+            scan("(+)").to_json(),
+            concat!(
+                r#"{"tokens":["#,
+                r#"{"type":"LEFT_PAREN","lexeme":"(","literal":null,"line":1},"#,
+                r#"{"type":"PLUS","lexeme":"+","literal":null,"line":1},"#,
+                r#"{"type":"RIGHT_PAREN","lexeme":")","literal":null,"line":1},"#,
+                r#"{"type":"EOF","lexeme":"","literal":null,"line":1}"#,
+                r#"],"errors":[]}"#
+            )
+        );
+        assert_eq!(
+            scan("@").to_json(),
+            r#"{"tokens":[{"type":"EOF","lexeme":"","literal":null,"line":1}],"errors":[{"line":1,"message":"Unexpected character: @"}]}"#
+        );
+    }
+
+    #[test]
+    fn errors_are_empty_without_lexical_errors() {
+        assert!(scan("1 + 2").errors().is_empty());
+
+        let scanner = scan("\n@");
+        assert_eq!(scanner.errors().len(), 1);
+        assert_eq!(scanner.errors()[0].message(), "Unexpected character: @");
+        assert_eq!(scanner.errors()[0].line(), 2);
+    }
+
+    #[test]
+    fn keep_comments_emits_comment_tokens() {
+        let config = ScannerConfig {
+            keep_comments: true,
+            ..ScannerConfig::default()
+        };
+        let mut scanner = Scanner::with_config("1 // one\n/* two */".to_string(), config);
+        scanner.scan_tokens();
+        let types: Vec<&TokenType> = scanner.tokens().iter().map(Token::token_type).collect();
+        assert_eq!(
+            types,
+            [
+                &TokenType::Number,
+                &TokenType::Comment(" one".to_string()),
+                &TokenType::Comment(" two ".to_string()),
+                &TokenType::Eof
+            ]
+        );
+        assert_eq!(token_types("1 // one"), [TokenType::Number, TokenType::Eof]);
+    }
+
+    #[test]
+    fn token_types_map_to_categories() {
+        let categories: Vec<String> = scan("var x = 42 + (\"s\");")
+            .tokens()
+            .iter()
+            .filter_map(|token| token.token_type().category())
+            .map(|category| category.to_string())
+            .collect();
+        assert_eq!(
+            categories,
+            [
+                "keyword",
+                "identifier",
+                "operator",
+                "literal",
+                "operator",
+                "punctuation",
+                "literal",
+                "punctuation",
+                "punctuation"
+            ]
+        );
+    }
+
+    #[test]
+    fn numbers_carry_a_float_literal() {
+        let literals: Vec<String> = scan("123 1234.1234 200.00")
+            .tokens()
+            .iter()
+            .map(Token::to_string)
+            .collect();
+        assert_eq!(
+            literals,
+            [
+                "NUMBER 123 123.0",
+                "NUMBER 1234.1234 1234.1234",
+                "NUMBER 200.00 200.0",
+                "EOF  null"
+            ]
+        );
+        assert_eq!(
+            token_types("123."),
+            [TokenType::Number, TokenType::Dot, TokenType::Eof]
+        );
+    }
+
+    #[test]
+    fn multi_byte_characters_stay_intact_in_strings() {
+        let scanner = scan("\"héllo\" // ünïcode\n+");
+        let tokens = scanner.tokens();
+        assert_eq!(tokens[0].lexeme(), "\"héllo\"");
+        assert_eq!(tokens[0].literal(), Some("héllo"));
+        assert_eq!(tokens[1].token_type(), &TokenType::Plus);
+        assert!(!scanner.has_errors());
+    }
+
+    #[test]
+    fn leading_zeros_scan_as_one_decimal_number() {
+        for (source, literal) in [("0", "0.0"), ("007", "7.0"), ("00", "0.0"), ("0.0", "0.0")] {
+            let scanner = scan(source);
+            let tokens = scanner.tokens();
+            assert_eq!(tokens.len(), 2, "{} should be a single number", source);
+            assert_eq!(tokens[0].token_type(), &TokenType::Number);
+            assert_eq!(tokens[0].lexeme(), source);
+            assert_eq!(tokens[0].literal(), Some(literal));
         }
+    }
+
+    #[test]
+    fn multi_line_tokens_are_positioned_at_their_first_character() {
+        let scanner = scan("x \"a\nbcdef\" y");
+        let string = &scanner.tokens()[1];
+        assert_eq!(
+            string.to_string_with_position(),
+            "1:3 STRING \"a\nbcdef\" a\nbcdef"
+        );
+        assert_eq!((string.start_line(), string.line()), (1, 2));
+        assert_eq!(
+            scanner.tokens()[2].to_string_with_position(),
+            "2:8 IDENTIFIER y null"
+        );
+
+        let config = ScannerConfig {
+            emit_whitespace: true,
+            ..ScannerConfig::default()
+        };
+        let mut scanner = Scanner::with_config("x \n\n y".to_string(), config);
+        scanner.scan_tokens();
+        let whitespace = &scanner.tokens()[1];
+        assert_eq!(whitespace.token_type(), &TokenType::Whitespace);
+        assert_eq!((whitespace.start_line(), whitespace.column()), (1, 2));
+    }
+
+    #[test]
+    fn reset_reuses_the_token_buffer() {
+        let mut scanner = Scanner::new("1 + 2 * 3".to_string());
+        scanner.scan_tokens();
+        let buffer = scanner.tokens().as_ptr();
+        let capacity = scanner.tokens.capacity();
 
-        Ok(self.tokens.iter().for_each(|token| {
-            writeln!(f, "{}", token.to_string()).expect("Failed to represent TokenType as string");
-        }))
+        scanner.reset("4 - 5".to_string());
+        scanner.scan_tokens();
+        assert_eq!(scanner.tokens().as_ptr(), buffer);
+        assert_eq!(scanner.tokens.capacity(), capacity);
+        assert_eq!(scanner.tokens().len(), 4);
     }
 }
@@ -0,0 +1,86 @@
+use crate::parser::{Expr, Stmt};
+
+/// A pass over the AST. Each method defaults to walking into the node's
+/// children and doing nothing else, so a pass only overrides the nodes it
+/// cares about and calls the matching `walk_*` function to keep descending.
+pub trait Visitor {
+    fn visit_stmt(&mut self, stmt: &Stmt) {
+        walk_stmt(self, stmt);
+    }
+
+    fn visit_expr(&mut self, expr: &Expr) {
+        walk_expr(self, expr);
+    }
+}
+
+/// Visits every statement of a program in order.
+pub fn walk<V: Visitor + ?Sized>(visitor: &mut V, statements: &[Stmt]) {
+    for statement in statements {
+        visitor.visit_stmt(statement);
+    }
+}
+
+/// Visits the expressions directly contained in `stmt`.
+pub fn walk_stmt<V: Visitor + ?Sized>(visitor: &mut V, stmt: &Stmt) {
+    match stmt {
+        Stmt::Print { values, .. } => {
+            for value in values {
+                visitor.visit_expr(value);
+            }
+        }
+        Stmt::Expression { expr, .. } => visitor.visit_expr(expr),
+        Stmt::Var { initializer, .. } => {
+            if let Some(initializer) = initializer {
+                visitor.visit_expr(initializer);
+            }
+        }
+    }
+}
+
+/// Visits the operands of `expr`, left to right.
+pub fn walk_expr<V: Visitor + ?Sized>(visitor: &mut V, expr: &Expr) {
+    match expr {
+        Expr::Literal(_) | Expr::Variable { .. } => {}
+        Expr::Grouping(inner) => visitor.visit_expr(inner),
+        Expr::Unary { right, .. } => visitor.visit_expr(right),
+        Expr::Binary { left, right, .. } | Expr::Logical { left, right, .. } => {
+            visitor.visit_expr(left);
+            visitor.visit_expr(right);
+        }
+        Expr::Assign { value, .. } => visitor.visit_expr(value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+    use crate::scanner::Scanner;
+
+    #[derive(Default)]
+    struct BinaryCounter {
+        count: usize,
+    }
+
+    impl Visitor for BinaryCounter {
+        fn visit_expr(&mut self, expr: &Expr) {
+            if let Expr::Binary { .. } = expr {
+                self.count += 1;
+            }
+            walk_expr(self, expr);
+        }
+    }
+
+    #[test]
+    fn visitor_counts_binary_nodes() {
+        let mut scanner = Scanner::new(
+            "var x = 1 + 2 * 3;\nprint (x - 1) == -x or !(x < 2);\nx = x / 2;".to_string(),
+        );
+        scanner.scan_tokens();
+        let statements = Parser::new(scanner.tokens()).parse().unwrap();
+
+        let mut counter = BinaryCounter::default();
+        walk(&mut counter, &statements);
+        assert_eq!(counter.count, 6);
+    }
+}
@@ -0,0 +1,28 @@
+use codecrafters_interpreter::{LoxError, Scanner, ScannerConfig, Token, TokenType};
+
+#[test]
+fn scanner_is_usable_through_the_library_root() {
+    let mut scanner = Scanner::with_config("print 1; @".to_string(), ScannerConfig::default());
+    scanner.scan_tokens();
+
+    let types: Vec<&TokenType> = scanner.tokens().iter().map(Token::token_type).collect();
+    assert_eq!(
+        types,
+        [
+            &TokenType::Print,
+            &TokenType::Number,
+            &TokenType::Semicolon,
+            &TokenType::Eof
+        ]
+    );
+    let messages: Vec<&str> = scanner.errors().iter().map(LoxError::message).collect();
+    assert_eq!(messages, ["Unexpected character: @"]);
+}
+
+#[test]
+fn errors_display_like_their_reports() {
+    let mut scanner = Scanner::new("\n@".to_string());
+    scanner.scan_tokens();
+    let error: &dyn std::error::Error = &scanner.errors()[0];
+    assert_eq!(error.to_string(), "[line 2] Error: Unexpected character: @");
+}
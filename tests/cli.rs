@@ -0,0 +1,371 @@
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+
+/// Writes `source` to a file unique to the calling test and returns its path.
+fn source_file(name: &str, source: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!(
+        "codecrafters-interpreter-{}-{}.lox",
+        std::process::id(),
+        name
+    ));
+    fs::write(&path, source).expect("temp file should be writable");
+    path
+}
+
+/// Runs the interpreter binary with `args` followed by a file holding `source`.
+fn run(name: &str, args: &[&str], source: &str) -> Output {
+    let path = source_file(name, source);
+    let output = Command::new(env!("CARGO_BIN_EXE_codecrafters-interpreter"))
+        .args(args)
+        .arg(&path)
+        .output()
+        .expect("binary should run");
+    let _ = fs::remove_file(path);
+    output
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+#[test]
+fn tokenize_exits_65_on_lexical_error_but_still_prints_tokens() {
+    let output = run("lexical-error", &["tokenize"], "( $ )");
+    assert_eq!(output.status.code(), Some(65));
+    assert_eq!(
+        stdout(&output),
+        "LEFT_PAREN ( null\nRIGHT_PAREN ) null\nEOF  null\n\n"
+    );
+    assert_eq!(stderr(&output), "[line 1] Error: Unexpected character: $\n");
+}
+
+#[test]
+fn unreadable_file_exits_66() {
+    let output = Command::new(env!("CARGO_BIN_EXE_codecrafters-interpreter"))
+        .args(["tokenize", "/nonexistent/input.lox"])
+        .output()
+        .expect("binary should run");
+    assert_eq!(output.status.code(), Some(66));
+    assert_eq!(stdout(&output), "");
+}
+
+#[test]
+fn skip_errors_drops_unexpected_characters() {
+    let output = run("skip-errors", &["tokenize", "--skip-errors"], "(@)@\n+");
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        "LEFT_PAREN ( null\nRIGHT_PAREN ) null\nPLUS + null\nEOF  null\n\n"
+    );
+    assert_eq!(stderr(&output), "");
+}
+
+#[test]
+fn skip_errors_still_reports_other_lexical_errors() {
+    let output = run(
+        "skip-errors-string",
+        &["tokenize", "--skip-errors"],
+        "\"abc",
+    );
+    assert_eq!(output.status.code(), Some(65));
+    assert_eq!(stderr(&output), "[line 1] Error: Unterminated string.\n");
+}
+
+#[test]
+fn exit_zero_on_lex_error_overrides_the_exit_code() {
+    let with_flag = run("exit-zero", &["tokenize", "--exit-zero-on-lex-error"], "@");
+    assert_eq!(with_flag.status.code(), Some(0));
+    assert_eq!(
+        stderr(&with_flag),
+        "[line 1] Error: Unexpected character: @\n"
+    );
+
+    let without_flag = run("exit-nonzero", &["tokenize"], "@");
+    assert_eq!(without_flag.status.code(), Some(65));
+}
+
+#[test]
+fn unknown_flags_are_usage_errors() {
+    let output = run("unknown-flag", &["tokenize", "--chek"], "1");
+    assert_eq!(output.status.code(), Some(64));
+    assert_eq!(stdout(&output), "");
+    assert!(stderr(&output).starts_with("Unknown flag: --chek\n"));
+}
+
+#[test]
+fn options_without_a_value_are_usage_errors() {
+    let output = run("missing-value", &["tokenize", "--color"], "1");
+    assert_eq!(output.status.code(), Some(64));
+    assert!(stderr(&output).starts_with("--color expects a value\n"));
+}
+
+#[test]
+fn unterminated_string_is_reported_at_its_opening_line() {
+    let output = run("unterminated-string", &["tokenize"], "1\n\"a\n\nb");
+    assert_eq!(output.status.code(), Some(65));
+    assert_eq!(stderr(&output), "[line 2] Error: Unterminated string.\n");
+}
+
+#[test]
+fn undefined_variable_exits_70() {
+    let output = run("undefined-variable", &["run"], "var y = 1;\nprint x;");
+    assert_eq!(output.status.code(), Some(70));
+    assert_eq!(stderr(&output), "Undefined variable 'x'.\n[line 2]\n");
+}
+
+#[test]
+fn evaluate_prints_the_value() {
+    let output = run("evaluate-grouping", &["evaluate"], "(2 + 3) * 4");
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "20\n");
+
+    let output = run("evaluate-bang-nil", &["evaluate"], "!nil");
+    assert_eq!(stdout(&output), "true\n");
+}
+
+#[test]
+fn run_executes_print_statements_in_order() {
+    let output = run("run-print", &["run"], "print \"hello\"; print 42;");
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "hello\n42\n");
+}
+
+#[test]
+fn uninitialized_variable_prints_nil() {
+    let output = run(
+        "uninitialized",
+        &["run"],
+        "var x;\nprint x;\nx = 1;\nprint x;",
+    );
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "nil\n1\n");
+}
+
+#[test]
+fn dash_reads_the_source_from_stdin() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_codecrafters-interpreter"))
+        .args(["tokenize", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("binary should run");
+    child.stdin.take().unwrap().write_all(b"*").unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    assert!(stdout(&output).contains("STAR * null\n"));
+}
+
+#[test]
+fn missing_command_exits_64() {
+    let output = Command::new(env!("CARGO_BIN_EXE_codecrafters-interpreter"))
+        .output()
+        .expect("binary should run");
+    assert_eq!(output.status.code(), Some(64));
+    assert!(stderr(&output).starts_with("No command given.\n"));
+}
+
+#[test]
+fn unknown_command_exits_64() {
+    let output = run("unknown-command", &["frobnicate"], "1");
+    assert_eq!(output.status.code(), Some(64));
+    assert!(stderr(&output).starts_with("Unknown command: frobnicate\n"));
+}
+
+#[test]
+fn check_is_silent_on_a_clean_file() {
+    let output = run(
+        "check-clean",
+        &["tokenize", "--check"],
+        "var x = 1;\nprint x;",
+    );
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "");
+    assert_eq!(stderr(&output), "");
+}
+
+#[test]
+fn check_reports_errors_without_tokens() {
+    let output = run("check-error", &["tokenize", "--check"], "var x = 1;\n#");
+    assert_eq!(output.status.code(), Some(65));
+    assert_eq!(stdout(&output), "");
+    assert_eq!(stderr(&output), "[line 2] Error: Unexpected character: #\n");
+}
+
+#[test]
+fn color_always_paints_errors() {
+    let output = run("color-always", &["tokenize", "--color", "always"], "@");
+    assert_eq!(
+        stderr(&output),
+        "[line \x1b[36m1\x1b[0m] \x1b[31mError\x1b[0m: Unexpected character: @\n"
+    );
+}
+
+#[test]
+fn color_never_prints_plain_errors() {
+    let output = run("color-never", &["tokenize", "--color", "never"], "@");
+    assert_eq!(stderr(&output), "[line 1] Error: Unexpected character: @\n");
+}
+
+#[test]
+fn dump_constants_prints_only_literals() {
+    let output = run(
+        "dump-constants",
+        &["tokenize", "--dump-constants"],
+        "var greeting = \"hi\";\nprint greeting + 42 * 1.5; // \"not a string\"",
+    );
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "hi\n42.0\n1.5\n");
+}
+
+#[test]
+fn parse_prints_the_ast() {
+    let output = run("parse-ast", &["parse"], "-3 * (4.5)");
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "(* (- 3.0) (group 4.5))\n");
+}
+
+#[test]
+fn parse_errors_exit_65() {
+    let output = run("parse-error", &["parse"], "(1 +");
+    assert_eq!(output.status.code(), Some(65));
+    assert_eq!(stdout(&output), "");
+    assert_eq!(
+        stderr(&output),
+        "[line 1] Error at end: Expect expression.\n"
+    );
+
+    let output = run("parse-trailing", &["evaluate"], "1e100");
+    assert_eq!(output.status.code(), Some(65));
+    assert_eq!(
+        stderr(&output),
+        "[line 1] Error at 'e100': Expect end of expression.\n"
+    );
+}
+
+#[test]
+fn multi_print_joins_values_with_spaces() {
+    let output = run(
+        "multi-print",
+        &["run", "--multi-print"],
+        "print 1, \"x\", true;",
+    );
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "1 x true\n");
+
+    let output = run("single-print", &["run"], "print 1, \"x\", true;");
+    assert_eq!(output.status.code(), Some(65));
+    assert_eq!(
+        stderr(&output),
+        "[line 1] Error at ',': Expect ';' after value.\n"
+    );
+}
+
+#[test]
+fn bench_prints_output_once_and_reports_timings() {
+    let output = run(
+        "bench",
+        &["bench", "--iterations", "3"],
+        "var x = 1;\nprint x + 1;",
+    );
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "2\n");
+
+    let stats = stderr(&output);
+    assert!(stats.starts_with("3 iterations: min "), "{}", stats);
+    assert!(
+        stats.contains(", median ") && stats.contains(", max "),
+        "{}",
+        stats
+    );
+    assert!(
+        stats.ends_with('\n') && stats.lines().count() == 1,
+        "{}",
+        stats
+    );
+}
+
+#[test]
+fn bench_rejects_zero_iterations() {
+    let output = run("bench-zero", &["bench", "--iterations", "0"], "print 1;");
+    assert_eq!(output.status.code(), Some(64));
+}
+
+#[test]
+fn run_step_pauses_before_each_statement() {
+    let path = source_file("step", "var x = 1;\nprint x;");
+    let mut child = Command::new(env!("CARGO_BIN_EXE_codecrafters-interpreter"))
+        .args(["run", "--step"])
+        .arg(&path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("binary should run");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"\np x\nc\n")
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    let _ = fs::remove_file(path);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "1\n");
+    assert_eq!(
+        stderr(&output),
+        "[line 1] (var x 1.0)\n(step) [line 2] (print x)\n(step) 1\n(step) "
+    );
+}
+
+#[test]
+fn flags_other_commands_take_are_usage_errors() {
+    for (command, flag) in [
+        ("run", "--json"),
+        ("parse", "--skip-errors"),
+        ("evaluate", "--check"),
+        ("tokenize", "--multi-print"),
+    ] {
+        let output = run("unsupported-flag", &[command, flag], "print 1;");
+        assert_eq!(output.status.code(), Some(64), "{} {}", command, flag);
+        assert!(
+            stderr(&output).starts_with(&format!("{} does not support {}\n", command, flag)),
+            "{}",
+            stderr(&output)
+        );
+        assert_eq!(stdout(&output), "");
+    }
+}
+
+#[test]
+fn invalid_color_values_are_usage_errors() {
+    let output = run("bad-color", &["tokenize", "--color", "sometimes"], "1");
+    assert_eq!(output.status.code(), Some(64));
+    assert_eq!(
+        stderr(&output),
+        "--color expects always, never or auto, got sometimes\n"
+    );
+    assert_eq!(stdout(&output), "");
+}
+
+#[test]
+fn repl_accepts_the_color_flag() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_codecrafters-interpreter"))
+        .args(["repl", "--color", "never"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("binary should run");
+    child.stdin.take().unwrap().write_all(b"1\n@\n").unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    assert!(stdout(&output).starts_with("> NUMBER 1 1.0\n"));
+    assert_eq!(stderr(&output), "[line 2] Error: Unexpected character: @\n");
+}